@@ -0,0 +1,2 @@
+-- Drop blog_posts and everything stored in it
+DROP TABLE IF EXISTS blog_posts;
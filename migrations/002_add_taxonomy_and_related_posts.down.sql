@@ -0,0 +1,15 @@
+-- Drop the related posts cache and the taxonomy columns
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache ON blog_posts;
+
+DROP FUNCTION IF EXISTS invalidate_related_posts_cache ();
+
+DROP TABLE IF EXISTS related_posts_cache;
+
+DROP INDEX IF EXISTS idx_blog_posts_series;
+
+DROP INDEX IF EXISTS idx_blog_posts_tags;
+
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS series_position,
+    DROP COLUMN IF EXISTS series,
+    DROP COLUMN IF EXISTS tags;
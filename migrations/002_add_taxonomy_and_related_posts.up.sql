@@ -0,0 +1,35 @@
+-- Tags and series membership on posts
+ALTER TABLE blog_posts
+    ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN series VARCHAR(255),
+    ADD COLUMN series_position INTEGER;
+
+CREATE INDEX idx_blog_posts_tags ON blog_posts USING GIN (tags);
+
+CREATE INDEX idx_blog_posts_series ON blog_posts (series)
+WHERE
+    series IS NOT NULL;
+
+-- Cached related posts, computed lazily per post
+CREATE TABLE related_posts_cache (
+    post_id UUID PRIMARY KEY REFERENCES blog_posts (id) ON DELETE CASCADE,
+    related_ids UUID[] NOT NULL DEFAULT '{}',
+    computed_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW ()
+);
+
+-- Saving any post can change the ranking of every other post, so the
+-- whole cache is dropped and rebuilt on the next read.
+CREATE OR REPLACE FUNCTION invalidate_related_posts_cache()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM related_posts_cache;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER invalidate_related_posts_cache
+    AFTER INSERT OR UPDATE OR DELETE ON blog_posts
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION invalidate_related_posts_cache();
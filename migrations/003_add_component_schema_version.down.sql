@@ -0,0 +1,6 @@
+-- Builds from before versioning read components as they are stored, so
+-- posts rewritten in a newer schema version may not render with them.
+DROP INDEX IF EXISTS idx_blog_posts_schema_version;
+
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS schema_version;
@@ -0,0 +1,6 @@
+-- Version of the component JSON each post was written with. Existing posts
+-- predate versioning and are upgraded from version 1 when read.
+ALTER TABLE blog_posts
+    ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX idx_blog_posts_schema_version ON blog_posts (schema_version);
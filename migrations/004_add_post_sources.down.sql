@@ -0,0 +1,5 @@
+-- Posts forget the files they were synced from
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS source_synced_at,
+    DROP COLUMN IF EXISTS source_hash,
+    DROP COLUMN IF EXISTS source_path;
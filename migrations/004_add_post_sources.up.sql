@@ -0,0 +1,9 @@
+-- Posts synced from the content directory remember their file, the hash of
+-- the file contents last synced and the post's updated_at at that moment, so
+-- edits made in the database since can be told apart from edits to the file.
+ALTER TABLE blog_posts
+    ADD COLUMN source_path TEXT UNIQUE,
+    ADD COLUMN source_hash VARCHAR(64),
+    ADD COLUMN source_synced_at TIMESTAMP
+    WITH
+        TIME ZONE;
@@ -0,0 +1,2 @@
+-- Drop users
+DROP TABLE IF EXISTS users;
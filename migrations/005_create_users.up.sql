@@ -0,0 +1,16 @@
+-- Users of the admin tooling. Passwords are stored as Argon2 PHC strings.
+CREATE TABLE users (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+    email VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW (),
+        updated_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW ()
+);
+
+-- Emails are unique regardless of case
+CREATE UNIQUE INDEX idx_users_email ON users (LOWER(email));
@@ -0,0 +1,2 @@
+-- The sample post is not restored; `ruststack-admin seed demo` loads it.
+SELECT 1;
@@ -0,0 +1,9 @@
+-- 001 inserted a sample post into every database. Demo content is now
+-- loaded on demand with `ruststack-admin seed demo`, so remove the sample
+-- wherever it was never edited.
+DELETE FROM blog_posts
+WHERE
+    slug = 'understanding-lifetimes'
+    AND title = 'Understanding Lifetimes in Rust'
+    AND updated_at = created_at
+    AND source_path IS NULL;
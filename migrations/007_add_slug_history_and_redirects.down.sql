@@ -0,0 +1,8 @@
+-- Drop redirects and the slug history
+DROP TABLE IF EXISTS redirects;
+
+DROP TRIGGER IF EXISTS record_slug_history ON blog_posts;
+
+DROP FUNCTION IF EXISTS record_slug_history ();
+
+DROP TABLE IF EXISTS slug_history;
@@ -0,0 +1,47 @@
+-- Slugs posts used to have, so links to them keep working. Recorded by a
+-- trigger, so every way of saving a post (admin API, sync, archive restore)
+-- is covered.
+CREATE TABLE slug_history (
+    slug VARCHAR(255) PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES blog_posts (id) ON DELETE CASCADE,
+    changed_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW ()
+);
+
+CREATE INDEX idx_slug_history_post_id ON slug_history (post_id);
+
+CREATE OR REPLACE FUNCTION record_slug_history()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- A slug a post uses now is nobody's old slug any more.
+    DELETE FROM slug_history WHERE slug = NEW.slug;
+
+    IF TG_OP = 'UPDATE' AND NEW.slug IS DISTINCT FROM OLD.slug THEN
+        INSERT INTO slug_history (slug, post_id)
+        VALUES (OLD.slug, NEW.id)
+        ON CONFLICT (slug)
+        DO UPDATE SET post_id = EXCLUDED.post_id, changed_at = NOW();
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_slug_history
+    AFTER INSERT OR UPDATE OF slug ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION record_slug_history();
+
+-- Arbitrary path-to-path redirects managed through the admin API
+CREATE TABLE redirects (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+    from_path TEXT UNIQUE NOT NULL,
+    to_path TEXT NOT NULL,
+    permanent BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW (),
+        updated_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW ()
+);
@@ -0,0 +1,2 @@
+-- Disable trigram similarity
+DROP EXTENSION IF EXISTS pg_trgm;
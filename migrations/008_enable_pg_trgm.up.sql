@@ -0,0 +1,3 @@
+-- Trigram similarity, used to suggest posts on the 404 page and to search
+-- post titles.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
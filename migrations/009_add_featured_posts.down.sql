@@ -0,0 +1,6 @@
+-- Drop featured posts
+DROP INDEX IF EXISTS idx_blog_posts_featured;
+
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS featured_position,
+    DROP COLUMN IF EXISTS featured;
@@ -0,0 +1,10 @@
+-- Posts featured on the homepage, lowest `featured_position` first and
+-- posts without one after, newest first. Featuring a post is not an edit,
+-- so it leaves `updated_at` alone.
+ALTER TABLE blog_posts
+    ADD COLUMN featured BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN featured_position INTEGER;
+
+CREATE INDEX idx_blog_posts_featured ON blog_posts (featured_position, published_at DESC)
+WHERE
+    featured;
@@ -0,0 +1,7 @@
+-- Drop post authors and the keyset pagination index
+DROP INDEX IF EXISTS idx_blog_posts_published_at_id;
+
+DROP INDEX IF EXISTS idx_blog_posts_author_id;
+
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS author_id;
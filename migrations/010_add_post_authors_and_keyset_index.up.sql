@@ -0,0 +1,13 @@
+-- Who wrote a post. Deleting the user keeps the post.
+ALTER TABLE blog_posts
+    ADD COLUMN author_id UUID REFERENCES users (id) ON DELETE SET NULL;
+
+CREATE INDEX idx_blog_posts_author_id ON blog_posts (author_id)
+WHERE
+    author_id IS NOT NULL;
+
+-- Keyset pagination of published posts walks (published_at, id) in either
+-- direction.
+CREATE INDEX idx_blog_posts_published_at_id ON blog_posts (published_at, id)
+WHERE
+    published_at IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Drop the related posts cache on every write to blog_posts again
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache ON blog_posts;
+
+CREATE TRIGGER invalidate_related_posts_cache
+    AFTER INSERT OR UPDATE OR DELETE ON blog_posts
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION invalidate_related_posts_cache();
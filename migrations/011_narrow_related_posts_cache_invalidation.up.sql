@@ -0,0 +1,10 @@
+-- Only changes that can move a post's ranking drop the related posts cache;
+-- featuring a post, crediting an author or recording where it was synced
+-- from leave it alone.
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache ON blog_posts;
+
+CREATE TRIGGER invalidate_related_posts_cache
+    AFTER INSERT OR DELETE OR UPDATE OF title, tags, series, components, published_at
+    ON blog_posts
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION invalidate_related_posts_cache();
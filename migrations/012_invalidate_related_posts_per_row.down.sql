@@ -0,0 +1,18 @@
+-- Drop the whole related posts cache on ranking changes again
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache_on_update ON blog_posts;
+
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache ON blog_posts;
+
+CREATE OR REPLACE FUNCTION invalidate_related_posts_cache()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM related_posts_cache;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER invalidate_related_posts_cache
+    AFTER INSERT OR DELETE OR UPDATE OF title, tags, series, components, published_at
+    ON blog_posts
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION invalidate_related_posts_cache();
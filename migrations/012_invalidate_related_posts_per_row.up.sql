@@ -0,0 +1,50 @@
+-- Drop only the cached rankings a change to a post can move: its own, those
+-- listing it, and those of posts sharing a tag or the series with it before
+-- or after the change. Those are the posts it is ranked against first.
+CREATE OR REPLACE FUNCTION invalidate_related_posts_cache()
+RETURNS TRIGGER AS $$
+DECLARE
+    changed_ids UUID[] := '{}';
+    changed_tags TEXT[] := '{}';
+    changed_series TEXT[] := '{}';
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        changed_ids := changed_ids || OLD.id;
+        changed_tags := changed_tags || OLD.tags;
+        changed_series := changed_series || OLD.series::TEXT;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        changed_ids := changed_ids || NEW.id;
+        changed_tags := changed_tags || NEW.tags;
+        changed_series := changed_series || NEW.series::TEXT;
+    END IF;
+
+    DELETE FROM related_posts_cache
+    WHERE post_id = ANY(changed_ids)
+       OR related_ids && changed_ids
+       OR post_id IN (
+           SELECT id FROM blog_posts
+           WHERE tags && changed_tags OR series = ANY(changed_series)
+       );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS invalidate_related_posts_cache ON blog_posts;
+
+CREATE TRIGGER invalidate_related_posts_cache
+    AFTER INSERT OR DELETE ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION invalidate_related_posts_cache();
+
+CREATE TRIGGER invalidate_related_posts_cache_on_update
+    AFTER UPDATE OF title, tags, series, components, published_at ON blog_posts
+    FOR EACH ROW
+    WHEN (
+        OLD.title IS DISTINCT FROM NEW.title
+        OR OLD.tags IS DISTINCT FROM NEW.tags
+        OR OLD.series IS DISTINCT FROM NEW.series
+        OR OLD.components IS DISTINCT FROM NEW.components
+        OR OLD.published_at IS DISTINCT FROM NEW.published_at
+    )
+    EXECUTE FUNCTION invalidate_related_posts_cache();
@@ -0,0 +1,7 @@
+-- Stop tracking content changes
+DROP TRIGGER IF EXISTS touch_content_updated_at ON blog_posts;
+
+DROP FUNCTION IF EXISTS touch_content_updated_at();
+
+ALTER TABLE blog_posts
+    DROP COLUMN IF EXISTS content_updated_at;
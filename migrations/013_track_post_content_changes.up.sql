@@ -0,0 +1,38 @@
+-- When what a content file defines about a post last changed, publishing
+-- aside: `updated_at` also moves when a post is published or unpublished,
+-- which content sync must not mistake for an edit. Set by a trigger, so
+-- every way of saving a post is covered. Upgrading components to a newer
+-- schema version leaves `updated_at` alone and is not an edit either.
+ALTER TABLE blog_posts
+    ADD COLUMN content_updated_at TIMESTAMP
+    WITH
+        TIME ZONE NOT NULL DEFAULT NOW ();
+
+-- A post published since its last sync can't be told from an edited one
+-- any more; it stays a conflict until synced with `--force`.
+UPDATE blog_posts
+SET
+    content_updated_at = updated_at;
+
+CREATE OR REPLACE FUNCTION touch_content_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.content_updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER touch_content_updated_at
+    BEFORE UPDATE OF title, slug, cover_image, tags, series, series_position, components
+    ON blog_posts
+    FOR EACH ROW
+    WHEN (
+        (OLD.title, OLD.slug, OLD.cover_image, OLD.tags, OLD.series, OLD.series_position)
+            IS DISTINCT FROM
+        (NEW.title, NEW.slug, NEW.cover_image, NEW.tags, NEW.series, NEW.series_position)
+        OR (
+            OLD.components IS DISTINCT FROM NEW.components
+            AND OLD.updated_at IS DISTINCT FROM NEW.updated_at
+        )
+    )
+    EXECUTE FUNCTION touch_content_updated_at();
@@ -0,0 +1,4 @@
+-- Drop the trigram indexes
+DROP INDEX IF EXISTS idx_blog_posts_title_trgm;
+
+DROP INDEX IF EXISTS idx_blog_posts_slug_trgm;
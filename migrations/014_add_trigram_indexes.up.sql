@@ -0,0 +1,6 @@
+-- Trigram indexes for the `%`, `<%` and `ILIKE` filters that suggest posts
+-- for missing pages (by slug) and search post titles, so neither reads
+-- every post.
+CREATE INDEX idx_blog_posts_slug_trgm ON blog_posts USING GIN (slug gin_trgm_ops);
+
+CREATE INDEX idx_blog_posts_title_trgm ON blog_posts USING GIN (title gin_trgm_ops);
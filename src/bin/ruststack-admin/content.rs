@@ -0,0 +1,190 @@
+use crate::output::Output;
+use crate::{Context, SeedSet};
+use anyhow::{bail, Result};
+use ruststack::services::content_sync::ContentSync;
+use ruststack::services::export::parse_archive;
+use ruststack::services::markdown_import::{post_from_markdown, MarkdownImportOptions};
+use ruststack::services::seed::{self, FixtureSet};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The outcome of importing one file.
+#[derive(Serialize)]
+struct FileImport {
+    path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    /// The converted post, on a dry run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Import every file, reporting failures per file. Fails if any file could
+/// not be imported.
+pub async fn import_markdown(
+    context: &Context,
+    files: &[PathBuf],
+    dry_run: bool,
+) -> Result<Output> {
+    let mut imports = Vec::new();
+    let mut lines = Vec::new();
+
+    for path in files {
+        let options = MarkdownImportOptions {
+            slug: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            dry_run,
+        };
+
+        let result = match std::fs::read_to_string(path) {
+            Ok(source) => post_from_markdown(&source, &options).map_err(anyhow::Error::from),
+            Err(error) => Err(error.into()),
+        };
+        let result = match result {
+            Ok(input) if dry_run => Ok((input.slug.clone(), Some(serde_json::to_value(&input)?))),
+            Ok(input) => context
+                .blog_service
+                .create_post(&input)
+                .await
+                .map(|post| (post.slug, None)),
+            Err(error) => Err(error),
+        };
+
+        let import = match result {
+            Ok((slug, post)) => {
+                match &post {
+                    Some(post) => lines.push(serde_json::to_string_pretty(post)?),
+                    None => lines.push(format!("Imported {} as /blog/{}", path.display(), slug)),
+                }
+                FileImport {
+                    path: path.clone(),
+                    // A dry run leaves generating the slug to the save.
+                    slug: Some(slug).filter(|slug| !slug.is_empty()),
+                    post,
+                    error: None,
+                }
+            }
+            Err(error) => {
+                lines.push(format!("Failed to import {}: {}", path.display(), error));
+                FileImport {
+                    path: path.clone(),
+                    slug: None,
+                    post: None,
+                    error: Some(error.to_string()),
+                }
+            }
+        };
+        imports.push(import);
+    }
+
+    let failed = imports
+        .iter()
+        .filter(|import| import.error.is_some())
+        .count();
+    if failed > 0 {
+        lines.push(format!(
+            "{} of {} file(s) failed to import",
+            failed,
+            files.len()
+        ));
+    }
+
+    let output = Output::new(&imports, lines.join("\n"))?;
+    Ok(if failed > 0 { output.failed() } else { output })
+}
+
+pub async fn import_archive(context: &Context, file: &Path) -> Result<Output> {
+    let value = serde_json::from_slice(&std::fs::read(file)?)?;
+    let archive = parse_archive(value)?;
+    let imported = context.blog_service.import_archive(&archive).await?;
+
+    Output::new(
+        &serde_json::json!({ "imported": imported }),
+        format!("Imported {} post(s) from {}", imported, file.display()),
+    )
+}
+
+/// Without `output`, the archive itself is the output, with or without
+/// `--json`.
+pub async fn export_archive(context: &Context, output: Option<&Path>) -> Result<Output> {
+    let archive = context.blog_service.export_archive().await?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, serde_json::to_string_pretty(&archive)?)?;
+            Output::new(
+                &serde_json::json!({ "exported": archive.posts.len(), "path": path }),
+                format!(
+                    "Exported {} post(s) to {}",
+                    archive.posts.len(),
+                    path.display()
+                ),
+            )
+        }
+        None => Output::new(&archive, serde_json::to_string_pretty(&archive)?),
+    }
+}
+
+/// Fails when any file could not be synced.
+pub async fn sync_content(context: &Context, force: bool) -> Result<Output> {
+    let Some(dir) = context.config.content_dir.clone() else {
+        bail!("CONTENT_DIR is not set");
+    };
+
+    let report = ContentSync::new(context.blog_service.clone(), dir)
+        .sync(force)
+        .await?;
+
+    let mut lines = vec![format!(
+        "{} created, {} updated, {} unchanged",
+        report.created.len(),
+        report.updated.len(),
+        report.unchanged
+    )];
+    for conflict in &report.conflicts {
+        lines.push(format!("conflict: {}: {}", conflict.path, conflict.message));
+    }
+    for error in &report.errors {
+        lines.push(format!("error: {}: {}", error.path, error.message));
+    }
+    for slug in &report.missing {
+        lines.push(format!("missing: file of {} no longer exists", slug));
+    }
+
+    let output = Output::new(&report, lines.join("\n"))?;
+    Ok(if report.conflicts.is_empty() && report.errors.is_empty() {
+        output
+    } else {
+        output.failed()
+    })
+}
+
+pub async fn upgrade_components(context: &Context) -> Result<Output> {
+    let upgraded = context.blog_service.upgrade_all_components().await?;
+    Output::new(
+        &serde_json::json!({ "upgraded": upgraded }),
+        format!(
+            "Upgraded {} post(s) to the current component schema",
+            upgraded
+        ),
+    )
+}
+
+pub async fn seed(context: &Context, set: SeedSet, posts: usize) -> Result<Output> {
+    let set = match set {
+        SeedSet::Demo => FixtureSet::Demo,
+        SeedSet::LoadTest => FixtureSet::LoadTest { posts },
+        SeedSet::E2e => FixtureSet::E2e,
+    };
+    let report = seed::seed(&context.blog_service, set).await?;
+    Output::new(
+        &report,
+        format!(
+            "Created {} {} post(s), {} already existed",
+            report.created, report.set, report.skipped
+        ),
+    )
+}
@@ -0,0 +1,287 @@
+//! `ruststack-admin`: manage posts, users and the database from the command
+//! line, sharing the server's configuration and services.
+//!
+//! Every command prints text by default and a single JSON document with
+//! `--json`, and exits with status 1 on failure.
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use ruststack::config::Config;
+use ruststack::database::Database;
+use ruststack::services::blog::BlogService;
+use ruststack::services::redirects::RedirectService;
+use ruststack::services::seed::DEFAULT_LOAD_TEST_POSTS;
+use ruststack::services::users::UserService;
+use ruststack::telemetry::Telemetry;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod content;
+mod migrate;
+mod output;
+mod posts;
+mod redirects;
+mod users;
+
+use output::Output;
+
+#[derive(Parser)]
+#[command(version, about = "Administer a ruststack site from the command line")]
+struct Cli {
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create, list, publish and delete posts
+    #[command(subcommand)]
+    Posts(PostsCommand),
+    /// Create users and reset their passwords
+    #[command(subcommand)]
+    Users(UsersCommand),
+    /// Manage redirects from paths the site no longer serves
+    #[command(subcommand)]
+    Redirects(RedirectsCommand),
+    /// Import Markdown articles with front matter as posts
+    ImportMarkdown {
+        /// Markdown files to import; slugs default to the file names
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Print the converted posts instead of saving them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Restore the posts of a site archive, overwriting posts with the same id
+    ImportArchive {
+        /// Archive file written by `export-archive`
+        file: PathBuf,
+    },
+    /// Write every post to a site archive (JSON)
+    ExportArchive {
+        /// File to write; prints the archive when omitted
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Sync posts from the content directory (`CONTENT_DIR`) once
+    SyncContent {
+        /// Let files overwrite posts edited in the database since the last
+        /// sync, and adopt posts of the same slug created elsewhere
+        #[arg(long)]
+        force: bool,
+    },
+    /// Apply, inspect and revert database migrations
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Rewrite posts stored with an older component schema version
+    UpgradeComponents,
+    /// Load a set of fixture posts, skipping ones that already exist
+    Seed {
+        #[arg(value_enum, default_value_t)]
+        set: SeedSet,
+        /// Number of posts to generate for `load-test`
+        #[arg(long, default_value_t = DEFAULT_LOAD_TEST_POSTS)]
+        posts: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum PostsCommand {
+    /// List posts, drafts included
+    List {
+        #[arg(long, value_enum, default_value_t)]
+        status: StatusFilter,
+    },
+    /// Create a post from a `.json` (admin API format) or Markdown file
+    Create { file: PathBuf },
+    /// Publish a post, now unless `--at` is given; published posts keep
+    /// their date
+    Publish {
+        slug: String,
+        /// Publication date as an RFC 3339 timestamp
+        #[arg(long)]
+        at: Option<DateTime<Utc>>,
+    },
+    /// Turn a post back into a draft
+    Unpublish { slug: String },
+    /// Feature a post on the homepage; lower positions come first
+    Feature {
+        slug: String,
+        #[arg(long)]
+        position: Option<i32>,
+    },
+    /// Stop featuring a post
+    Unfeature { slug: String },
+    /// List featured posts in homepage order
+    Featured,
+    /// Credit a post to the user with `email`, or to nobody when omitted
+    SetAuthor { slug: String, email: Option<String> },
+    /// Delete a post
+    Delete { slug: String },
+    /// Export a post as Markdown or a standalone HTML page
+    Export {
+        slug: String,
+        #[arg(long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// File to write; prints the post when omitted
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RedirectsCommand {
+    /// List redirects
+    List,
+    /// Redirect a path to another path or an http(s) URL
+    Add {
+        from_path: String,
+        to_path: String,
+        /// Answer `302 Found` instead of `301 Moved Permanently`
+        #[arg(long)]
+        temporary: bool,
+    },
+    /// Delete a redirect
+    Delete { id: Uuid },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Apply pending migrations
+    Up,
+    /// List migrations and whether they are applied
+    Status,
+    /// Revert the newest applied migration, or every one newer than `--to`
+    Down {
+        /// Version to revert to; 0 reverts everything
+        #[arg(long)]
+        to: Option<i64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum UsersCommand {
+    /// Create a user; a password is generated unless `--password-stdin`
+    Create {
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        name: String,
+        /// Read the password from the first line of stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// List users
+    List,
+    /// Set a new password; one is generated unless `--password-stdin`
+    ResetPassword {
+        email: String,
+        /// Read the password from the first line of stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum StatusFilter {
+    #[default]
+    All,
+    Published,
+    Draft,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum SeedSet {
+    /// A couple of hand-written posts for local development
+    #[default]
+    Demo,
+    /// Thousands of generated posts
+    LoadTest,
+    /// Posts end-to-end tests rely on
+    E2e,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+/// What the commands share.
+struct Context {
+    config: Config,
+    database: Database,
+    blog_service: Arc<BlogService>,
+    user_service: UserService,
+    redirect_service: RedirectService,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    // Keep stdout for the command's output.
+    Telemetry::init_subscriber("ruststack-admin", "warn".into(), std::io::stderr);
+
+    dotenvy::dotenv().ok();
+
+    let result = match connect().await {
+        Ok(context) => run(&context, cli.command).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(output) => {
+            output.print(cli.json);
+            if !output.is_success() {
+                std::process::exit(1);
+            }
+        }
+        Err(error) => {
+            output::print_error(&error, cli.json);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn connect() -> anyhow::Result<Context> {
+    let config = Config::from_env()?;
+    let database = Database::connect(&config.database_url).await?;
+
+    Ok(Context {
+        blog_service: Arc::new(BlogService::new(database.clone())),
+        user_service: UserService::new(database.clone()),
+        redirect_service: RedirectService::new(database.clone()),
+        database,
+        config,
+    })
+}
+
+async fn run(context: &Context, command: Command) -> anyhow::Result<Output> {
+    // Only migrations may run against a schema that is behind.
+    if !matches!(command, Command::Migrate(_)) {
+        context.database.check_schema().await?;
+    }
+
+    match command {
+        Command::Posts(command) => posts::run(context, command).await,
+        Command::Users(command) => users::run(context, command).await,
+        Command::Redirects(command) => redirects::run(context, command).await,
+        Command::ImportMarkdown { files, dry_run } => {
+            content::import_markdown(context, &files, dry_run).await
+        }
+        Command::ImportArchive { file } => content::import_archive(context, &file).await,
+        Command::ExportArchive { output } => {
+            content::export_archive(context, output.as_deref()).await
+        }
+        Command::SyncContent { force } => content::sync_content(context, force).await,
+        Command::Migrate(command) => migrate::run(context, command).await,
+        Command::UpgradeComponents => content::upgrade_components(context).await,
+        Command::Seed { set, posts } => content::seed(context, set, posts).await,
+    }
+}
@@ -0,0 +1,81 @@
+use crate::output::Output;
+use crate::{Context, MigrateCommand};
+use anyhow::Result;
+use ruststack::models::migration::MigrationState;
+
+pub async fn run(context: &Context, command: MigrateCommand) -> Result<Output> {
+    match command {
+        MigrateCommand::Up => {
+            let applied = context.database.migrate().await?;
+            let text = match applied.as_slice() {
+                [] => "Database schema is up to date".to_string(),
+                versions => format!("Applied migration(s) {}", list(versions)),
+            };
+            Output::new(&serde_json::json!({ "applied": applied }), text)
+        }
+        MigrateCommand::Status => {
+            let status = context.database.migration_status().await?;
+            let text = status
+                .iter()
+                .map(|migration| {
+                    let applied_at = migration
+                        .applied_at
+                        .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default();
+                    format!(
+                        "{:>4}  {:<9}  {:<19}  {}{}",
+                        migration.version,
+                        migration.state.label(),
+                        applied_at,
+                        migration.description,
+                        if migration.reversible {
+                            ""
+                        } else {
+                            " (irreversible)"
+                        }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Output::new(&status, text)
+        }
+        MigrateCommand::Down { to } => {
+            let target = match to {
+                Some(to) => to,
+                None => previous_version(context).await?,
+            };
+            let reverted = context.database.revert_migrations(target).await?;
+            let text = match reverted.as_slice() {
+                [] => "Nothing to revert".to_string(),
+                versions => format!("Reverted migration(s) {}", list(versions)),
+            };
+            Output::new(&serde_json::json!({ "reverted": reverted }), text)
+        }
+    }
+}
+
+/// The version before the newest applied migration, so that reverting to it
+/// undoes exactly one migration.
+async fn previous_version(context: &Context) -> Result<i64> {
+    let applied: Vec<i64> = context
+        .database
+        .migration_status()
+        .await?
+        .iter()
+        .filter(|migration| migration.state != MigrationState::Pending)
+        .map(|migration| migration.version)
+        .collect();
+
+    Ok(match applied.as_slice() {
+        [.., previous, _] => *previous,
+        _ => 0,
+    })
+}
+
+fn list(versions: &[i64]) -> String {
+    versions
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
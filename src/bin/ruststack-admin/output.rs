@@ -0,0 +1,63 @@
+use anyhow::Result;
+use ruststack::services::validation::ValidationErrors;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+/// The result of a command: printed as JSON with `--json`, as text
+/// otherwise.
+pub struct Output {
+    value: Value,
+    text: String,
+    success: bool,
+}
+
+impl Output {
+    pub fn new(value: &impl Serialize, text: impl Into<String>) -> Result<Self> {
+        Ok(Output {
+            value: serde_json::to_value(value)?,
+            text: text.into(),
+            success: true,
+        })
+    }
+
+    /// Print the output but exit with a failure, e.g. when some files of a
+    /// batch could not be imported.
+    pub fn failed(mut self) -> Self {
+        self.success = false;
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    /// Write errors are ignored: a closed pipe (`| head`) is not a failure
+    /// of the command.
+    pub fn print(&self, json: bool) {
+        let mut stdout = std::io::stdout();
+        let _ = if json {
+            writeln!(stdout, "{}", self.value)
+        } else if !self.text.is_empty() {
+            writeln!(stdout, "{}", self.text)
+        } else {
+            Ok(())
+        };
+    }
+}
+
+/// Print an error to stdout as `{"error": ..., "errors": [...]}` with
+/// `--json`, so scripts only have to parse one stream, or to stderr
+/// otherwise.
+pub fn print_error(error: &anyhow::Error, json: bool) {
+    if !json {
+        eprintln!("error: {:#}", error);
+        return;
+    }
+
+    let mut value = serde_json::json!({ "error": format!("{:#}", error) });
+    if let Some(errors) = error.downcast_ref::<ValidationErrors>() {
+        value["errors"] = serde_json::json!(errors.errors);
+    }
+    println!("{}", value);
+}
@@ -0,0 +1,196 @@
+use crate::output::Output;
+use crate::{Context, ExportFormat, PostsCommand, StatusFilter};
+use anyhow::{bail, Context as _, Result};
+use ruststack::models::blog::{AuthorInput, BlogPost, BlogPostSummary, FeaturedInput};
+use ruststack::services::content_sync::parse_post_file;
+use ruststack::services::export::post_to_markdown;
+use ruststack::templates::TemplateEngine;
+use std::path::Path;
+
+pub async fn run(context: &Context, command: PostsCommand) -> Result<Output> {
+    match command {
+        PostsCommand::List { status } => list(context, status).await,
+        PostsCommand::Create { file } => create(context, &file).await,
+        PostsCommand::Publish { slug, at } => {
+            let post = context.blog_service.publish_post(&slug, at).await?;
+            saved(post, &slug, "Published")
+        }
+        PostsCommand::Unpublish { slug } => {
+            let post = context.blog_service.unpublish_post(&slug).await?;
+            saved(post, &slug, "Unpublished")
+        }
+        PostsCommand::Feature { slug, position } => {
+            set_featured(context, &slug, true, position).await
+        }
+        PostsCommand::Unfeature { slug } => set_featured(context, &slug, false, None).await,
+        PostsCommand::Featured => featured(context).await,
+        PostsCommand::SetAuthor { slug, email } => {
+            set_author(context, &slug, email.as_deref()).await
+        }
+        PostsCommand::Delete { slug } => {
+            if !context.blog_service.delete_post(&slug).await? {
+                bail!("No post with slug `{}`", slug);
+            }
+            Output::new(
+                &serde_json::json!({ "deleted": slug }),
+                format!("Deleted {}", slug),
+            )
+        }
+        PostsCommand::Export {
+            slug,
+            format,
+            output,
+        } => export(context, &slug, format, output.as_deref()).await,
+    }
+}
+
+async fn list(context: &Context, status: StatusFilter) -> Result<Output> {
+    let posts: Vec<BlogPostSummary> = context
+        .blog_service
+        .list_all_posts()
+        .await?
+        .iter()
+        .filter(|post| match status {
+            StatusFilter::All => true,
+            StatusFilter::Published => post.published_at.is_some(),
+            StatusFilter::Draft => post.published_at.is_none(),
+        })
+        .map(BlogPost::summary)
+        .collect();
+
+    let text = posts
+        .iter()
+        .map(|post| {
+            let status = match post.published_at {
+                Some(published_at) => published_at.format("%Y-%m-%d").to_string(),
+                None => "draft".to_string(),
+            };
+            format!("{:<10}  {:<40}  {}", status, post.slug, post.title)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Output::new(&posts, text)
+}
+
+async fn create(context: &Context, file: &Path) -> Result<Output> {
+    let source = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let input = parse_post_file(file, &source)?;
+    let post = context.blog_service.create_post(&input).await?;
+
+    let text = format!("Created /blog/{} ({})", post.slug, post.id);
+    Output::new(&post.summary(), text)
+}
+
+async fn set_featured(
+    context: &Context,
+    slug: &str,
+    featured: bool,
+    position: Option<i32>,
+) -> Result<Output> {
+    let Some(post) = context.blog_service.get_any_post_by_slug(slug).await? else {
+        bail!("No post with slug `{}`", slug);
+    };
+    let input = FeaturedInput { featured, position };
+    let Some(post) = context.blog_service.set_featured(post.id, &input).await? else {
+        bail!("No post with slug `{}`", slug);
+    };
+
+    let text = match (featured, post.featured_position) {
+        (false, _) => format!("Unfeatured {}", slug),
+        (true, Some(position)) => format!("Featured {} at position {}", slug, position),
+        (true, None) => format!("Featured {}", slug),
+    };
+    Output::new(&post, text)
+}
+
+async fn set_author(context: &Context, slug: &str, email: Option<&str>) -> Result<Output> {
+    let Some(post) = context.blog_service.get_any_post_by_slug(slug).await? else {
+        bail!("No post with slug `{}`", slug);
+    };
+    let author = match email {
+        Some(email) => match context.user_service.get_user_by_email(email).await? {
+            Some(user) => Some(user),
+            None => bail!("No user with email `{}`", email),
+        },
+        None => None,
+    };
+    let input = AuthorInput {
+        author_id: author.as_ref().map(|user| user.id),
+    };
+    let Some(post) = context.blog_service.set_author(post.id, &input).await? else {
+        bail!("No post with slug `{}`", slug);
+    };
+
+    let text = match author {
+        Some(user) => format!("Credited {} to {}", slug, user.email),
+        None => format!("Removed the author of {}", slug),
+    };
+    Output::new(&post, text)
+}
+
+async fn featured(context: &Context) -> Result<Output> {
+    let posts = context.blog_service.list_featured().await?;
+    let text = posts
+        .iter()
+        .map(|featured| {
+            let position = featured
+                .featured_position
+                .map(|position| position.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let status = if featured.post.published_at.is_some() {
+                ""
+            } else {
+                "  (draft)"
+            };
+            format!(
+                "{:>4}  {:<40}  {}{}",
+                position, featured.post.slug, featured.post.title, status
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Output::new(&posts, text)
+}
+
+async fn export(
+    context: &Context,
+    slug: &str,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<Output> {
+    let Some(post) = context.blog_service.get_any_post_by_slug(slug).await? else {
+        bail!("No post with slug `{}`", slug);
+    };
+
+    let body = match format {
+        ExportFormat::Markdown => post_to_markdown(&post),
+        ExportFormat::Html => {
+            TemplateEngine::new(&context.config)?.render_standalone_post(&post)?
+        }
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &body)?;
+            Output::new(
+                &serde_json::json!({ "slug": post.slug, "path": path }),
+                format!("Exported {} to {}", post.slug, path.display()),
+            )
+        }
+        None => Output::new(
+            &serde_json::json!({ "slug": post.slug, "content": body }),
+            body,
+        ),
+    }
+}
+
+fn saved(post: Option<BlogPost>, slug: &str, verb: &str) -> Result<Output> {
+    let Some(post) = post else {
+        bail!("No post with slug `{}`", slug);
+    };
+    let text = format!("{} {}", verb, post.slug);
+    Output::new(&post.summary(), text)
+}
@@ -0,0 +1,52 @@
+use crate::output::Output;
+use crate::{Context, RedirectsCommand};
+use anyhow::{bail, Result};
+use ruststack::models::redirect::RedirectInput;
+
+pub async fn run(context: &Context, command: RedirectsCommand) -> Result<Output> {
+    match command {
+        RedirectsCommand::List => {
+            let redirects = context.redirect_service.list().await?;
+            let text = redirects
+                .iter()
+                .map(|redirect| {
+                    format!(
+                        "{}  {}  {} -> {}",
+                        redirect.id,
+                        if redirect.permanent { 301 } else { 302 },
+                        redirect.from_path,
+                        redirect.to_path
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Output::new(&redirects, text)
+        }
+        RedirectsCommand::Add {
+            from_path,
+            to_path,
+            temporary,
+        } => {
+            let input = RedirectInput {
+                from_path,
+                to_path,
+                permanent: !temporary,
+            };
+            let redirect = context.redirect_service.create(&input).await?;
+            let text = format!(
+                "Redirecting {} to {} ({})",
+                redirect.from_path, redirect.to_path, redirect.id
+            );
+            Output::new(&redirect, text)
+        }
+        RedirectsCommand::Delete { id } => {
+            if !context.redirect_service.delete(id).await? {
+                bail!("No redirect with id {}", id);
+            }
+            Output::new(
+                &serde_json::json!({ "deleted": id }),
+                format!("Deleted redirect {}", id),
+            )
+        }
+    }
+}
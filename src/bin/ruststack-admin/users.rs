@@ -0,0 +1,75 @@
+use crate::output::Output;
+use crate::{Context, UsersCommand};
+use anyhow::{bail, Result};
+use ruststack::models::user::User;
+use ruststack::services::users::generate_password;
+use serde::Serialize;
+
+/// A user, with the password when it was generated for them.
+#[derive(Serialize)]
+struct UserWithPassword<'a> {
+    #[serde(flatten)]
+    user: &'a User,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'a str>,
+}
+
+pub async fn run(context: &Context, command: UsersCommand) -> Result<Output> {
+    match command {
+        UsersCommand::Create {
+            email,
+            name,
+            password_stdin,
+        } => {
+            let (password, generated) = password(password_stdin)?;
+            let user = context
+                .user_service
+                .create_user(&email, &name, &password)
+                .await?;
+            with_password(&user, generated.then_some(&password), "Created user")
+        }
+        UsersCommand::List => {
+            let users = context.user_service.list_users().await?;
+            let text = users
+                .iter()
+                .map(|user| format!("{:<40}  {}", user.email, user.name))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Output::new(&users, text)
+        }
+        UsersCommand::ResetPassword {
+            email,
+            password_stdin,
+        } => {
+            let (password, generated) = password(password_stdin)?;
+            let Some(user) = context
+                .user_service
+                .reset_password(&email, &password)
+                .await?
+            else {
+                bail!("No user with email {}", email);
+            };
+            with_password(&user, generated.then_some(&password), "Reset password of")
+        }
+    }
+}
+
+/// The password from stdin, or a generated one. Also says whether it was
+/// generated, so it can be shown once.
+fn password(from_stdin: bool) -> Result<(String, bool)> {
+    if !from_stdin {
+        return Ok((generate_password(), true));
+    }
+
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok((line.trim_end_matches(['\r', '\n']).to_string(), false))
+}
+
+fn with_password(user: &User, password: Option<&str>, verb: &str) -> Result<Output> {
+    let mut text = format!("{} {} ({})", verb, user.email, user.id);
+    if let Some(password) = password {
+        text.push_str(&format!("\nPassword: {}", password));
+    }
+    Output::new(&UserWithPassword { user, password }, text)
+}
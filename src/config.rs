@@ -1,8 +1,26 @@
+use crate::models::blog::EmbedProvider;
+use std::path::PathBuf;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub host: String,
     pub port: u16,
+    /// Bearer token guarding `/api/admin`. The admin API is disabled when unset.
+    pub admin_token: Option<String>,
+    /// Third-party providers the `embed` component may load, from the
+    /// comma-separated `EMBED_PROVIDERS` (defaults to all of them).
+    pub embed_providers: Vec<EmbedProvider>,
+    /// Public URL of the site, without a trailing slash. Exported pages
+    /// resolve site-relative links against it.
+    pub site_url: String,
+    /// Directory of post files (`CONTENT_DIR`) synced into the database at
+    /// startup and on change. Disabled when unset.
+    pub content_dir: Option<PathBuf>,
+    /// Whether the server applies pending migrations at startup
+    /// (`AUTO_MIGRATE`, on by default). Either way it refuses to serve a
+    /// database whose schema is behind.
+    pub auto_migrate: bool,
 }
 
 impl Config {
@@ -15,6 +33,32 @@ impl Config {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .unwrap_or(8080),
+            admin_token: std::env::var("ADMIN_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            embed_providers: std::env::var("EMBED_PROVIDERS")
+                .map(|names| {
+                    names
+                        .split(',')
+                        .filter_map(EmbedProvider::from_name)
+                        .collect()
+                })
+                .unwrap_or_else(|_| EmbedProvider::ALL.to_vec()),
+            site_url: std::env::var("SITE_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| "https://ruststack.dev".to_string()),
+            content_dir: std::env::var("CONTENT_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            auto_migrate: std::env::var("AUTO_MIGRATE")
+                .map(|value| {
+                    !matches!(
+                        value.trim().to_ascii_lowercase().as_str(),
+                        "0" | "false" | "no" | "off"
+                    )
+                })
+                .unwrap_or(true),
         })
     }
 }
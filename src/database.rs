@@ -1,6 +1,31 @@
-use crate::models::blog::{BlogPost, BlogPostSummary};
+use crate::models::blog::{
+    component_list, components_from_json, ArchiveMonth, ArchiveYear, BlogComponent, BlogPost,
+    BlogPostInput, BlogPostSummary, FeaturedEntry, FeaturedPost, OldSlug, PostSource,
+    RelatedCandidate, SeriesSummary, SourceFile, TagSummary,
+};
+use crate::models::component_schema::{upgrade_components, CURRENT_SCHEMA_VERSION};
+use crate::models::listing::{PostCursor, PostFilter, PostOwner, PostSort};
+use crate::models::migration::{MigrationState, MigrationStatus};
+use crate::models::redirect::{Redirect, RedirectInput};
+use crate::models::user::User;
 use anyhow::Result;
-use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::{postgres::PgRow, Connection, PgConnection, PgPool, Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Migrations embedded from `migrations/`. Each one is reversible:
+/// `NNN_name.up.sql` applies it and `NNN_name.down.sql` reverts it.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Advisory lock held while migrating or checking the schema, so instances
+/// starting at the same time take turns instead of racing.
+const MIGRATION_LOCK_KEY: i64 = 0x7275_7374_7374_6163;
+
+/// Trigram similarity (0 to 1) a post needs to be suggested for a missing
+/// page or found by a search.
+const SIMILARITY_THRESHOLD: f32 = 0.25;
 
 #[derive(Clone)]
 pub struct Database {
@@ -8,18 +33,114 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self> {
+    /// Connect without touching the schema; see [`Database::migrate`].
+    pub async fn connect(database_url: &str) -> Result<Self> {
         let pool = PgPool::connect(database_url).await?;
+        Ok(Database { pool })
+    }
 
-        // Run migrations
-        sqlx::migrate!("./migrations").run(&pool).await?;
+    /// Apply pending migrations. Returns the versions applied.
+    pub async fn migrate(&self) -> Result<Vec<i64>> {
+        let mut conn = self.lock_migrations().await?;
+        let pending: Vec<i64> = migration_status(&mut conn)
+            .await?
+            .iter()
+            .filter(|migration| migration.state == MigrationState::Pending)
+            .map(|migration| migration.version)
+            .collect();
 
-        Ok(Database { pool })
+        MIGRATOR.run(&mut conn).await?;
+        conn.close().await?;
+        Ok(pending)
+    }
+
+    /// Revert every applied migration newer than `target`, newest first.
+    /// Returns the versions reverted.
+    pub async fn revert_migrations(&self, target: i64) -> Result<Vec<i64>> {
+        let mut conn = self.lock_migrations().await?;
+        let mut reverted = Vec::new();
+        for migration in migration_status(&mut conn).await?.iter().rev() {
+            if migration.version <= target || migration.state == MigrationState::Pending {
+                continue;
+            }
+            if !migration.reversible {
+                anyhow::bail!(
+                    "migration {} ({}) can't be reverted by this build",
+                    migration.version,
+                    migration.description
+                );
+            }
+            reverted.push(migration.version);
+        }
+
+        MIGRATOR.undo(&mut conn, target).await?;
+        conn.close().await?;
+        Ok(reverted)
+    }
+
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        let mut conn = self.pool.acquire().await?;
+        migration_status(&mut conn).await
+    }
+
+    /// Fail unless every migration of this build is applied as it is now.
+    /// Waits for an instance that is migrating to finish first.
+    pub async fn check_schema(&self) -> Result<()> {
+        let mut conn = self.lock_migrations().await?;
+        let status = migration_status(&mut conn).await?;
+        conn.close().await?;
+
+        let mut behind = Vec::new();
+        for migration in &status {
+            match migration.state {
+                MigrationState::Applied => {}
+                MigrationState::Unknown => tracing::warn!(
+                    version = migration.version,
+                    "Database has a migration this build doesn't know about: {}",
+                    migration.description
+                ),
+                state => behind.push(format!(
+                    "{} {} ({})",
+                    migration.version,
+                    migration.description,
+                    state.label()
+                )),
+            }
+        }
+
+        if !behind.is_empty() {
+            anyhow::bail!(
+                "database schema is not up to date: {}; run `ruststack-admin migrate up`",
+                behind.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// A connection holding the migration lock. It is taken out of the
+    /// pool, so the lock is released with the connection even when an
+    /// error skips closing it.
+    async fn lock_migrations(&self) -> Result<PgConnection> {
+        let mut conn = self.pool.acquire().await?.detach();
+
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .fetch_one(&mut conn)
+            .await?;
+        if !locked {
+            tracing::info!("Waiting for another instance to finish migrating");
+            sqlx::query("SELECT pg_advisory_lock($1)")
+                .bind(MIGRATION_LOCK_KEY)
+                .execute(&mut conn)
+                .await?;
+        }
+        Ok(conn)
     }
 
     pub async fn get_blog_post_by_slug(&self, slug: &str) -> Result<Option<BlogPost>> {
         let row = sqlx::query(
-            "SELECT id, title, slug, published_at, cover_image, components, created_at, updated_at
+            "SELECT id, title, slug, published_at, cover_image, tags, series, series_position,
+                    components, schema_version, author_id, created_at, updated_at
              FROM blog_posts
              WHERE slug = $1 AND published_at IS NOT NULL",
         )
@@ -27,27 +148,358 @@ impl Database {
         .fetch_optional(&self.pool)
         .await?;
 
-        if let Some(row) = row {
-            let components_json: serde_json::Value = row.get("components");
-            let post = BlogPost {
-                id: row.get("id"),
-                title: row.get("title"),
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Fetch a post by id regardless of whether it is published.
+    pub async fn get_blog_post_by_id(&self, id: Uuid) -> Result<Option<BlogPost>> {
+        let row = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, series, series_position,
+                    components, schema_version, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Fetch the published posts among `ids`, in no particular order.
+    pub async fn get_blog_posts_by_ids(&self, ids: &[Uuid]) -> Result<Vec<BlogPost>> {
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, series, series_position,
+                    components, schema_version, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE id = ANY($1) AND published_at IS NOT NULL",
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(post_from_row).collect()
+    }
+
+    /// Fetch a post by slug regardless of whether it is published.
+    pub async fn get_any_blog_post_by_slug(&self, slug: &str) -> Result<Option<BlogPost>> {
+        let row = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, series, series_position,
+                    components, schema_version, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE slug = $1",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Insert a post, remembering `source` as the file it was synced from.
+    pub async fn insert_blog_post(
+        &self,
+        input: &BlogPostInput,
+        source: Option<SourceFile<'_>>,
+    ) -> Result<BlogPost> {
+        let row = sqlx::query(
+            "INSERT INTO blog_posts
+                (title, slug, published_at, cover_image, tags, series, series_position, components,
+                 schema_version, source_path, source_hash, source_synced_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                     CASE WHEN $10::text IS NOT NULL THEN NOW() END)
+             RETURNING id, title, slug, published_at, cover_image, tags, series, series_position,
+                       components, schema_version, author_id, created_at, updated_at",
+        )
+        .bind(&input.title)
+        .bind(&input.slug)
+        .bind(input.published_at)
+        .bind(&input.cover_image)
+        .bind(&input.tags)
+        .bind(&input.series)
+        .bind(input.series_position)
+        .bind(serde_json::to_value(&input.components)?)
+        .bind(CURRENT_SCHEMA_VERSION)
+        .bind(source.map(|source| source.path))
+        .bind(source.map(|source| source.hash))
+        .fetch_one(&self.pool)
+        .await?;
+
+        post_from_row(&row)
+    }
+
+    /// Insert every post whose slug is not taken yet, in one transaction.
+    /// Returns how many were inserted.
+    pub async fn insert_missing_blog_posts(&self, inputs: &[BlogPostInput]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+
+        for input in inputs {
+            let result = sqlx::query(
+                "INSERT INTO blog_posts
+                    (title, slug, published_at, cover_image, tags, series, series_position,
+                     components, schema_version)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 ON CONFLICT (slug) DO NOTHING",
+            )
+            .bind(&input.title)
+            .bind(&input.slug)
+            .bind(input.published_at)
+            .bind(&input.cover_image)
+            .bind(&input.tags)
+            .bind(&input.series)
+            .bind(input.series_position)
+            .bind(serde_json::to_value(&input.components)?)
+            .bind(CURRENT_SCHEMA_VERSION)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected() as usize;
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Update a post, remembering `source` as the file it was synced from.
+    pub async fn update_blog_post(
+        &self,
+        id: Uuid,
+        input: &BlogPostInput,
+        source: Option<SourceFile<'_>>,
+    ) -> Result<Option<BlogPost>> {
+        let row = sqlx::query(
+            "UPDATE blog_posts
+             SET title = $2, slug = COALESCE(NULLIF($3, ''), slug), published_at = $4, cover_image = $5, tags = $6,
+                 series = $7, series_position = $8, components = $9, schema_version = $10,
+                 updated_at = NOW(),
+                 source_path = COALESCE($11, source_path),
+                 source_hash = COALESCE($12, source_hash),
+                 source_synced_at = CASE WHEN $11::text IS NOT NULL THEN NOW() ELSE source_synced_at END
+             WHERE id = $1
+             RETURNING id, title, slug, published_at, cover_image, tags, series, series_position,
+                       components, schema_version, author_id, created_at, updated_at",
+        )
+        .bind(id)
+        .bind(&input.title)
+        .bind(&input.slug)
+        .bind(input.published_at)
+        .bind(&input.cover_image)
+        .bind(&input.tags)
+        .bind(&input.series)
+        .bind(input.series_position)
+        .bind(serde_json::to_value(&input.components)?)
+        .bind(CURRENT_SCHEMA_VERSION)
+        .bind(source.map(|source| source.path))
+        .bind(source.map(|source| source.hash))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Publish the post with `slug` at `published_at`. An already published
+    /// post keeps its date unless one is given; a draft is published now.
+    pub async fn publish_blog_post(
+        &self,
+        slug: &str,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<BlogPost>> {
+        let row = sqlx::query(
+            "UPDATE blog_posts
+             SET published_at = COALESCE($2, published_at, NOW()), updated_at = NOW()
+             WHERE slug = $1
+             RETURNING id, title, slug, published_at, cover_image, tags, series, series_position,
+                       components, schema_version, author_id, created_at, updated_at",
+        )
+        .bind(slug)
+        .bind(published_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Turn the post with `slug` back into a draft.
+    pub async fn unpublish_blog_post(&self, slug: &str) -> Result<Option<BlogPost>> {
+        let row = sqlx::query(
+            "UPDATE blog_posts
+             SET published_at = NULL, updated_at = NOW()
+             WHERE slug = $1
+             RETURNING id, title, slug, published_at, cover_image, tags, series, series_position,
+                       components, schema_version, author_id, created_at, updated_at",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(post_from_row).transpose()
+    }
+
+    /// Delete the post with `slug`, returning whether there was one.
+    pub async fn delete_blog_post(&self, slug: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM blog_posts WHERE slug = $1")
+            .bind(slug)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every post, drafts included, oldest first.
+    pub async fn list_all_blog_posts(&self) -> Result<Vec<BlogPost>> {
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, series, series_position,
+                    components, schema_version, author_id, created_at, updated_at
+             FROM blog_posts
+             ORDER BY created_at, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(post_from_row).collect()
+    }
+
+    /// Write posts back exactly as given, keyed by id and keeping their
+    /// timestamps. Either every post is restored or none is.
+    /// Every featured post, drafts included.
+    pub async fn list_featured_entries(&self) -> Result<Vec<FeaturedEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, featured_position
+             FROM blog_posts
+             WHERE featured
+             ORDER BY featured_position NULLS LAST, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| FeaturedEntry {
+                post_id: row.get("id"),
+                position: row.get("featured_position"),
+            })
+            .collect())
+    }
+
+    pub async fn list_slug_history(&self) -> Result<Vec<OldSlug>> {
+        let rows = sqlx::query(
+            "SELECT slug, post_id, changed_at
+             FROM slug_history
+             ORDER BY changed_at, slug",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| OldSlug {
                 slug: row.get("slug"),
-                published_at: row.get("published_at"),
-                cover_image: row.get("cover_image"),
-                components: serde_json::from_value(components_json)?,
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            };
-            Ok(Some(post))
-        } else {
-            Ok(None)
+                post_id: row.get("post_id"),
+                changed_at: row.get("changed_at"),
+            })
+            .collect())
+    }
+
+    /// Restore a site archive in one transaction: posts by id, with their
+    /// timestamps and whether they are featured, then their old slugs, then
+    /// redirects by `from_path`.
+    pub async fn restore_archive(
+        &self,
+        posts: &[BlogPost],
+        featured: &[FeaturedEntry],
+        old_slugs: &[OldSlug],
+        redirects: &[Redirect],
+    ) -> Result<()> {
+        let positions: HashMap<Uuid, Option<i32>> = featured
+            .iter()
+            .map(|entry| (entry.post_id, entry.position))
+            .collect();
+        let mut tx = self.pool.begin().await?;
+
+        for post in posts {
+            let position = positions.get(&post.id);
+            sqlx::query(
+                "INSERT INTO blog_posts
+                    (id, title, slug, published_at, cover_image, tags, series, series_position,
+                     components, schema_version, author_id, created_at, updated_at,
+                     featured, featured_position)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                         (SELECT id FROM users WHERE id = $11), $12, $13, $14, $15)
+                 ON CONFLICT (id) DO UPDATE
+                 SET title = EXCLUDED.title, slug = EXCLUDED.slug,
+                     published_at = EXCLUDED.published_at, cover_image = EXCLUDED.cover_image,
+                     tags = EXCLUDED.tags, series = EXCLUDED.series,
+                     series_position = EXCLUDED.series_position, components = EXCLUDED.components,
+                     schema_version = EXCLUDED.schema_version, author_id = EXCLUDED.author_id,
+                     created_at = EXCLUDED.created_at,
+                     updated_at = EXCLUDED.updated_at,
+                     featured = EXCLUDED.featured,
+                     featured_position = EXCLUDED.featured_position",
+            )
+            .bind(post.id)
+            .bind(&post.title)
+            .bind(&post.slug)
+            .bind(post.published_at)
+            .bind(&post.cover_image)
+            .bind(&post.tags)
+            .bind(&post.series)
+            .bind(post.series_position)
+            .bind(component_list::serialize(
+                &post.components,
+                serde_json::value::Serializer,
+            )?)
+            .bind(CURRENT_SCHEMA_VERSION)
+            .bind(post.author_id)
+            .bind(post.created_at)
+            .bind(post.updated_at)
+            .bind(position.is_some())
+            .bind(position.copied().flatten())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // A slug a post uses now is nobody's old slug, as in the
+        // `record_slug_history` trigger.
+        for old_slug in old_slugs {
+            sqlx::query(
+                "INSERT INTO slug_history (slug, post_id, changed_at)
+                 SELECT $1, $2, $3
+                 WHERE NOT EXISTS (SELECT 1 FROM blog_posts WHERE slug = $1)
+                 ON CONFLICT (slug)
+                 DO UPDATE SET post_id = EXCLUDED.post_id, changed_at = EXCLUDED.changed_at",
+            )
+            .bind(&old_slug.slug)
+            .bind(old_slug.post_id)
+            .bind(old_slug.changed_at)
+            .execute(&mut *tx)
+            .await?;
         }
+
+        for redirect in redirects {
+            sqlx::query(
+                "INSERT INTO redirects (id, from_path, to_path, permanent, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (from_path) DO UPDATE
+                 SET to_path = EXCLUDED.to_path, permanent = EXCLUDED.permanent,
+                     updated_at = EXCLUDED.updated_at",
+            )
+            .bind(redirect.id)
+            .bind(&redirect.from_path)
+            .bind(&redirect.to_path)
+            .bind(redirect.permanent)
+            .bind(redirect.created_at)
+            .bind(redirect.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
     }
 
     pub async fn list_blog_posts(&self, limit: i64, offset: i64) -> Result<Vec<BlogPostSummary>> {
         let rows = sqlx::query(
-            "SELECT id, title, slug, published_at, cover_image, created_at, updated_at
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
              FROM blog_posts
              WHERE published_at IS NOT NULL
              ORDER BY published_at DESC
@@ -58,28 +510,927 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
 
+        Ok(rows.iter().map(summary_from_row).collect())
+    }
+
+    /// A page of the published posts matching `filter`, newest first, and
+    /// how many match in all. One statement, so the count always agrees
+    /// with the page, and is returned even for a page past the last.
+    pub async fn list_blog_posts_counted(
+        &self,
+        filter: &PostFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<BlogPostSummary>, i64)> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "WITH matching AS (
+                 SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
+                 FROM blog_posts
+                 WHERE published_at IS NOT NULL",
+        );
+        push_post_filter(&mut query, filter);
+        query
+            .push(
+                "),
+             page AS (
+                 SELECT * FROM matching
+                 ORDER BY published_at DESC, id DESC
+                 LIMIT ",
+            )
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset)
+            .push(
+                ")
+             SELECT total.count AS total, page.*
+             FROM (SELECT COUNT(*) AS count FROM matching) total
+             LEFT JOIN page ON TRUE
+             ORDER BY page.published_at DESC, page.id DESC",
+            );
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        let total = rows.first().map_or(0, |row| row.get("total"));
+        // Without posts on the page, the one row only carries the count.
         let posts = rows
-            .into_iter()
-            .map(|row| BlogPostSummary {
-                id: row.get("id"),
-                title: row.get("title"),
-                slug: row.get("slug"),
-                published_at: row.get("published_at"),
-                cover_image: row.get("cover_image"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })
+            .iter()
+            .filter(|row| row.get::<Option<Uuid>, _>("id").is_some())
+            .map(summary_from_row)
             .collect();
+        Ok((posts, total))
+    }
+
+    /// Post counts per year and month (UTC), newest first. Reads only the
+    /// `published_at` index.
+    pub async fn get_archive(&self) -> Result<Vec<ArchiveYear>> {
+        let rows = sqlx::query(
+            "SELECT EXTRACT(YEAR FROM month)::INTEGER AS year,
+                    EXTRACT(MONTH FROM month)::INTEGER AS month,
+                    posts
+             FROM (
+                 SELECT date_trunc('month', published_at AT TIME ZONE 'UTC') AS month,
+                        COUNT(*) AS posts
+                 FROM blog_posts
+                 WHERE published_at IS NOT NULL
+                 GROUP BY 1
+             ) AS months
+             ORDER BY month DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(posts)
+        let mut years: Vec<ArchiveYear> = Vec::new();
+        for row in &rows {
+            let year: i32 = row.get("year");
+            let month = ArchiveMonth {
+                month: row.get::<i32, _>("month") as u32,
+                posts: row.get("posts"),
+            };
+            match years.last_mut() {
+                Some(last) if last.year == year => {
+                    last.posts += month.posts;
+                    last.months.push(month);
+                }
+                _ => years.push(ArchiveYear {
+                    year,
+                    posts: month.posts,
+                    months: vec![month],
+                }),
+            }
+        }
+        Ok(years)
     }
 
-    pub async fn count_blog_posts(&self) -> Result<i64> {
-        let row =
-            sqlx::query("SELECT COUNT(*) as count FROM blog_posts WHERE published_at IS NOT NULL")
-                .fetch_one(&self.pool)
-                .await?;
+    /// Published posts whose slug is most like `slug`, best match first.
+    pub async fn get_posts_with_similar_slug(
+        &self,
+        slug: &str,
+        limit: i64,
+    ) -> Result<Vec<BlogPostSummary>> {
+        let mut tx = self.begin_similarity_search().await?;
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE published_at IS NOT NULL AND (slug % $1 OR $1 <% slug)
+             ORDER BY GREATEST(similarity(slug, $1), word_similarity($1, slug)) DESC,
+                      published_at DESC
+             LIMIT $2",
+        )
+        .bind(slug)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(rows.iter().map(summary_from_row).collect())
+    }
+
+    /// Published posts whose title contains `query` or words like it, best
+    /// match first.
+    pub async fn search_blog_posts(&self, query: &str, limit: i64) -> Result<Vec<BlogPostSummary>> {
+        let mut tx = self.begin_similarity_search().await?;
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE published_at IS NOT NULL AND (title ILIKE $2 OR $1 <% title)
+             ORDER BY CASE WHEN title ILIKE $2 THEN 1 ELSE word_similarity($1, title) END DESC,
+                      published_at DESC
+             LIMIT $3",
+        )
+        .bind(query)
+        .bind(contains_pattern(query))
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(rows.iter().map(summary_from_row).collect())
+    }
+
+    /// A transaction in which pg_trgm's `%` and `<%` operators match at
+    /// [`SIMILARITY_THRESHOLD`], so similarity filters can use the trigram
+    /// indexes.
+    async fn begin_similarity_search(&self) -> Result<sqlx::Transaction<'static, Postgres>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "SELECT set_config('pg_trgm.similarity_threshold', $1, true),
+                    set_config('pg_trgm.word_similarity_threshold', $1, true)",
+        )
+        .bind(SIMILARITY_THRESHOLD.to_string())
+        .execute(&mut *tx)
+        .await?;
+        Ok(tx)
+    }
+
+    /// Up to `limit` published posts matching `filter` in `sort` order,
+    /// starting after `cursor`.
+    pub async fn list_blog_posts_page(
+        &self,
+        filter: &PostFilter,
+        sort: PostSort,
+        cursor: Option<&PostCursor>,
+        limit: i64,
+    ) -> Result<Vec<BlogPostSummary>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE published_at IS NOT NULL",
+        );
+        push_post_filter(&mut query, filter);
+
+        let (comparison, order) = match sort {
+            PostSort::Newest => ("<", "DESC"),
+            PostSort::Oldest => (">", "ASC"),
+        };
+        if let Some(cursor) = cursor {
+            query
+                .push(format_args!(" AND (published_at, id) {} (", comparison))
+                .push_bind(cursor.published_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(format_args!(
+                " ORDER BY published_at {0}, id {0} LIMIT ",
+                order
+            ))
+            .push_bind(limit);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(summary_from_row).collect())
+    }
+
+    /// Pages of the published posts of each of `owners`, in one query: up
+    /// to `limit` posts of each, in `sort` order and after `cursor`.
+    pub async fn list_blog_post_pages(
+        &self,
+        owners: &[PostOwner],
+        sort: PostSort,
+        cursor: Option<&PostCursor>,
+        limit: i64,
+    ) -> Result<Vec<(PostOwner, BlogPostSummary)>> {
+        let mut author_ids = Vec::new();
+        let mut tags = Vec::new();
+        for owner in owners {
+            match owner {
+                PostOwner::Author(id) => author_ids.push(*id),
+                PostOwner::Tag(tag) => tags.push(tag.clone()),
+            }
+        }
+
+        let (comparison, order) = match sort {
+            PostSort::Newest => ("<", "DESC"),
+            PostSort::Oldest => (">", "ASC"),
+        };
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT * FROM (
+                 SELECT *, ROW_NUMBER() OVER (
+                     PARTITION BY owner_author, owner_tag
+                     ORDER BY published_at {0}, id {0}
+                 ) AS row_number
+                 FROM (
+                     SELECT author_id AS owner_author, NULL::text AS owner_tag,
+                            id, title, slug, published_at, cover_image, tags, author_id,
+                            created_at, updated_at
+                     FROM blog_posts
+                     WHERE author_id = ANY(",
+            order
+        ));
+        // Containment per tag, which the GIN index can serve.
+        query.push_bind(author_ids).push(
+            ")
+                     UNION ALL
+                     SELECT NULL::uuid, tag,
+                            id, title, slug, published_at, cover_image, tags, author_id,
+                            created_at, updated_at
+                     FROM unnest(",
+        );
+        query.push_bind(tags).push(
+            "::text[]) AS tag
+                     JOIN blog_posts ON tags @> ARRAY[tag]
+                 ) owned
+                 WHERE published_at IS NOT NULL",
+        );
+        if let Some(cursor) = cursor {
+            query
+                .push(format_args!(" AND (published_at, id) {} (", comparison))
+                .push_bind(cursor.published_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(") pages WHERE row_number <= ")
+            .push_bind(limit)
+            .push(" ORDER BY row_number");
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let owner = match row.get::<Option<Uuid>, _>("owner_author") {
+                    Some(id) => PostOwner::Author(id),
+                    None => PostOwner::Tag(row.get("owner_tag")),
+                };
+                (owner, summary_from_row(row))
+            })
+            .collect())
+    }
+
+    /// Feature the post or stop featuring it. Returns `None` when there is
+    /// no post with `id`.
+    pub async fn set_blog_post_featured(
+        &self,
+        id: Uuid,
+        featured: bool,
+        position: Option<i32>,
+    ) -> Result<Option<FeaturedPost>> {
+        let row = sqlx::query(
+            "UPDATE blog_posts
+             SET featured = $2, featured_position = CASE WHEN $2 THEN $3 END
+             WHERE id = $1
+             RETURNING id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at,
+                       featured_position",
+        )
+        .bind(id)
+        .bind(featured)
+        .bind(position)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(featured_from_row))
+    }
+
+    /// Credit the post with `id` to `author_id`, or to nobody.
+    pub async fn set_blog_post_author(
+        &self,
+        id: Uuid,
+        author_id: Option<Uuid>,
+    ) -> Result<Option<BlogPostSummary>> {
+        let row = sqlx::query(
+            "UPDATE blog_posts
+             SET author_id = $2
+             WHERE id = $1
+             RETURNING id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at",
+        )
+        .bind(id)
+        .bind(author_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(summary_from_row))
+    }
+
+    /// Featured posts in homepage order, drafts included unless
+    /// `published_only`.
+    pub async fn list_featured_blog_posts(
+        &self,
+        published_only: bool,
+        limit: i64,
+    ) -> Result<Vec<FeaturedPost>> {
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at,
+                    featured_position
+             FROM blog_posts
+             WHERE featured AND (published_at IS NOT NULL OR NOT $1)
+             ORDER BY featured_position NULLS LAST, published_at DESC NULLS LAST
+             LIMIT $2",
+        )
+        .bind(published_only)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(featured_from_row).collect())
+    }
+
+    /// Series with published posts, the one with the newest part first.
+    pub async fn list_series_summaries(&self, limit: i64) -> Result<Vec<SeriesSummary>> {
+        let rows = sqlx::query(
+            "SELECT series AS name,
+                    COUNT(*) AS posts,
+                    (array_agg(slug ORDER BY series_position NULLS LAST, published_at))[1]
+                        AS first_slug,
+                    MAX(published_at) AS last_published_at
+             FROM blog_posts
+             WHERE series IS NOT NULL AND published_at IS NOT NULL
+             GROUP BY series
+             ORDER BY last_published_at DESC
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SeriesSummary {
+                name: row.get("name"),
+                posts: row.get("posts"),
+                first_slug: row.get("first_slug"),
+                last_published_at: row.get("last_published_at"),
+            })
+            .collect())
+    }
+
+    /// The published posts of each series in `names`, paired with the name,
+    /// in reading order: by position, then by publication.
+    pub async fn list_series_posts(
+        &self,
+        names: &[String],
+    ) -> Result<Vec<(String, BlogPostSummary)>> {
+        let rows = sqlx::query(
+            "SELECT series, id, title, slug, published_at, cover_image, tags, author_id,
+                    created_at, updated_at
+             FROM blog_posts
+             WHERE series = ANY($1) AND published_at IS NOT NULL
+             ORDER BY series, series_position NULLS LAST, published_at, id",
+        )
+        .bind(names)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("series"), summary_from_row(row)))
+            .collect())
+    }
+
+    /// Tags of published posts, the most used first.
+    pub async fn list_tag_summaries(&self, limit: i64) -> Result<Vec<TagSummary>> {
+        let rows = sqlx::query(
+            "SELECT tag AS name, COUNT(*) AS posts
+             FROM blog_posts, unnest(tags) AS tag
+             WHERE published_at IS NOT NULL
+             GROUP BY tag
+             ORDER BY posts DESC, tag
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TagSummary {
+                name: row.get("name"),
+                posts: row.get("posts"),
+            })
+            .collect())
+    }
+
+    /// Fetch published post summaries for `ids`, keeping the order of `ids`.
+    pub async fn get_blog_post_summaries(&self, ids: &[Uuid]) -> Result<Vec<BlogPostSummary>> {
+        let rows = sqlx::query(
+            "SELECT id, title, slug, published_at, cover_image, tags, author_id, created_at, updated_at
+             FROM blog_posts
+             WHERE id = ANY($1) AND published_at IS NOT NULL
+             ORDER BY array_position($1, id)",
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(summary_from_row).collect())
+    }
+
+    /// For each published post among `post_ids`, the post itself and up to
+    /// `limit` other published posts to rank against it, as
+    /// `(post_id, candidate)`: those sharing the most tags first, then those
+    /// in its series, then the newest.
+    pub async fn list_related_candidates(
+        &self,
+        post_ids: &[Uuid],
+        limit: i64,
+    ) -> Result<Vec<(Uuid, RelatedCandidate)>> {
+        let rows = sqlx::query(
+            "SELECT target.id AS post_id, candidate.*
+             FROM blog_posts target
+             CROSS JOIN LATERAL (
+                 SELECT id, tags, series, components, schema_version
+                 FROM blog_posts candidate
+                 WHERE candidate.published_at IS NOT NULL
+                 ORDER BY candidate.id = target.id DESC,
+                          cardinality(ARRAY(
+                              SELECT unnest(candidate.tags) INTERSECT SELECT unnest(target.tags)
+                          )) DESC,
+                          candidate.series IS NOT DISTINCT FROM target.series
+                              AND candidate.series IS NOT NULL DESC,
+                          candidate.published_at DESC
+                 LIMIT $2 + 1
+             ) candidate
+             WHERE target.id = ANY($1) AND target.published_at IS NOT NULL",
+        )
+        .bind(post_ids)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let candidate = RelatedCandidate {
+                    id: row.get("id"),
+                    tags: row.get("tags"),
+                    series: row.get("series"),
+                    components: components_from_row(&row)?,
+                };
+                Ok((row.get("post_id"), candidate))
+            })
+            .collect()
+    }
+
+    /// Posts whose components were written with an older schema version, as
+    /// `(id, schema_version, components)`.
+    pub async fn list_outdated_components(&self) -> Result<Vec<(Uuid, i32, serde_json::Value)>> {
+        let rows = sqlx::query(
+            "SELECT id, schema_version, components
+             FROM blog_posts
+             WHERE schema_version < $1
+             ORDER BY created_at",
+        )
+        .bind(CURRENT_SCHEMA_VERSION)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("id"),
+                    row.get("schema_version"),
+                    row.get("components"),
+                )
+            })
+            .collect())
+    }
+
+    /// Store upgraded components, unless the post was saved since they were
+    /// read. Returns whether the row was updated.
+    pub async fn store_upgraded_components(
+        &self,
+        id: Uuid,
+        from_version: i32,
+        components: &serde_json::Value,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE blog_posts
+             SET components = $3, schema_version = $4
+             WHERE id = $1 AND schema_version = $2",
+        )
+        .bind(id)
+        .bind(from_version)
+        .bind(components)
+        .bind(CURRENT_SCHEMA_VERSION)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// The post synced from `path`, or else the post using `slug`.
+    pub async fn get_post_source(&self, path: &str, slug: &str) -> Result<Option<PostSource>> {
+        let row = sqlx::query(
+            "SELECT id, slug, source_path, source_hash, content_updated_at, source_synced_at
+             FROM blog_posts
+             WHERE source_path = $1 OR slug = $2
+             ORDER BY source_path = $1 DESC NULLS LAST
+             LIMIT 1",
+        )
+        .bind(path)
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(source_from_row))
+    }
+
+    /// Every post synced from the content directory.
+    pub async fn list_post_sources(&self) -> Result<Vec<PostSource>> {
+        let rows = sqlx::query(
+            "SELECT id, slug, source_path, source_hash, content_updated_at, source_synced_at
+             FROM blog_posts
+             WHERE source_path IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(source_from_row).collect())
+    }
+
+    /// Slugs starting with `prefix` that posts have or used to have.
+    pub async fn get_slugs_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT slug FROM blog_posts WHERE slug LIKE $1 || '%'
+             UNION
+             SELECT slug FROM slug_history WHERE slug LIKE $1 || '%'",
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("slug")).collect())
+    }
+
+    /// Current slug of the published post that used to have `slug`.
+    pub async fn get_current_slug(&self, old_slug: &str) -> Result<Option<String>> {
+        let row = sqlx::query(
+            "SELECT p.slug
+             FROM slug_history h
+             JOIN blog_posts p ON p.id = h.post_id
+             WHERE h.slug = $1 AND p.published_at IS NOT NULL",
+        )
+        .bind(old_slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.get("slug")))
+    }
+
+    pub async fn get_redirect_by_path(&self, from_path: &str) -> Result<Option<Redirect>> {
+        let row = sqlx::query(
+            "SELECT id, from_path, to_path, permanent, created_at, updated_at
+             FROM redirects
+             WHERE from_path = $1",
+        )
+        .bind(from_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(redirect_from_row))
+    }
+
+    pub async fn list_redirects(&self) -> Result<Vec<Redirect>> {
+        let rows = sqlx::query(
+            "SELECT id, from_path, to_path, permanent, created_at, updated_at
+             FROM redirects
+             ORDER BY from_path",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(redirect_from_row).collect())
+    }
+
+    pub async fn insert_redirect(&self, input: &RedirectInput) -> Result<Redirect> {
+        let row = sqlx::query(
+            "INSERT INTO redirects (from_path, to_path, permanent)
+             VALUES ($1, $2, $3)
+             RETURNING id, from_path, to_path, permanent, created_at, updated_at",
+        )
+        .bind(&input.from_path)
+        .bind(&input.to_path)
+        .bind(input.permanent)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(redirect_from_row(&row))
+    }
+
+    /// Delete a redirect, returning whether there was one.
+    pub async fn delete_redirect(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM redirects WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_user(&self, email: &str, name: &str, password_hash: &str) -> Result<User> {
+        let row = sqlx::query(
+            "INSERT INTO users (email, name, password_hash)
+             VALUES ($1, $2, $3)
+             RETURNING id, email, name, created_at, updated_at",
+        )
+        .bind(email)
+        .bind(name)
+        .bind(password_hash)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user_from_row(&row))
+    }
+
+    pub async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "SELECT id, email, name, created_at, updated_at
+             FROM users
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    /// The users among `ids` credited with at least one published post.
+    pub async fn get_authors_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>> {
+        let rows = sqlx::query(
+            "SELECT id, email, name, created_at, updated_at
+             FROM users
+             WHERE id = ANY($1)
+               AND EXISTS (
+                   SELECT 1 FROM blog_posts
+                   WHERE author_id = users.id AND published_at IS NOT NULL
+               )",
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(user_from_row).collect())
+    }
+
+    /// Look a user up by email, ignoring case.
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "SELECT id, email, name, created_at, updated_at
+             FROM users
+             WHERE LOWER(email) = LOWER($1)",
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<User>> {
+        let rows = sqlx::query(
+            "SELECT id, email, name, created_at, updated_at
+             FROM users
+             ORDER BY created_at, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(user_from_row).collect())
+    }
+
+    pub async fn update_user_password(
+        &self,
+        email: &str,
+        password_hash: &str,
+    ) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "UPDATE users
+             SET password_hash = $2, updated_at = NOW()
+             WHERE LOWER(email) = LOWER($1)
+             RETURNING id, email, name, created_at, updated_at",
+        )
+        .bind(email)
+        .bind(password_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    /// Cached related post IDs of those of `post_ids` that have them.
+    pub async fn get_cached_related_ids_for(
+        &self,
+        post_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Uuid>>> {
+        let rows = sqlx::query(
+            "SELECT post_id, related_ids FROM related_posts_cache WHERE post_id = ANY($1)",
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("post_id"), row.get("related_ids")))
+            .collect())
+    }
+
+    pub async fn store_related_ids(&self, post_id: Uuid, related_ids: &[Uuid]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO related_posts_cache (post_id, related_ids, computed_at)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (post_id)
+             DO UPDATE SET related_ids = EXCLUDED.related_ids, computed_at = EXCLUDED.computed_at",
+        )
+        .bind(post_id)
+        .bind(related_ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Every migration known to this build or applied to the database, by
+/// version.
+async fn migration_status(conn: &mut PgConnection) -> Result<Vec<MigrationStatus>> {
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(&mut *conn)
+            .await?;
+    let rows = if table_exists {
+        sqlx::query(
+            "SELECT version, description, checksum, success, installed_on
+             FROM _sqlx_migrations",
+        )
+        .fetch_all(&mut *conn)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let mut applied: HashMap<i64, &PgRow> =
+        rows.iter().map(|row| (row.get("version"), row)).collect();
+
+    let mut status = Vec::new();
+    for migration in MIGRATOR.iter() {
+        if migration.migration_type.is_down_migration() {
+            continue;
+        }
+
+        let row = applied.remove(&migration.version);
+        let state = match row {
+            None => MigrationState::Pending,
+            Some(row) if !row.get::<bool, _>("success") => MigrationState::Failed,
+            Some(row) if row.get::<Vec<u8>, _>("checksum") != *migration.checksum => {
+                MigrationState::Modified
+            }
+            Some(_) => MigrationState::Applied,
+        };
+        status.push(MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            state,
+            applied_at: row.map(|row| row.get("installed_on")),
+            reversible: MIGRATOR.iter().any(|down| {
+                down.version == migration.version && down.migration_type.is_down_migration()
+            }),
+        });
+    }
+
+    for (version, row) in applied {
+        status.push(MigrationStatus {
+            version,
+            description: row.get("description"),
+            state: MigrationState::Unknown,
+            applied_at: Some(row.get("installed_on")),
+            reversible: false,
+        });
+    }
+
+    status.sort_by_key(|migration| migration.version);
+    Ok(status)
+}
+
+/// Append the conditions of `filter` to a query over `blog_posts` that
+/// already has a `WHERE` clause.
+fn push_post_filter(query: &mut QueryBuilder<'_, Postgres>, filter: &PostFilter) {
+    if let Some(tag) = &filter.tag {
+        // Containment rather than `= ANY(tags)`, which the GIN index can't serve.
+        query
+            .push(" AND tags @> ARRAY[")
+            .push_bind(tag.clone())
+            .push("]");
+    }
+    if let Some(author_id) = filter.author_id {
+        query.push(" AND author_id = ").push_bind(author_id);
+    }
+    if let Some(from) = filter.published_from {
+        query.push(" AND published_at >= ").push_bind(from);
+    }
+    if let Some(until) = filter.published_until {
+        query.push(" AND published_at < ").push_bind(until);
+    }
+    match filter.has_cover {
+        Some(true) => query.push(" AND cover_image IS NOT NULL"),
+        Some(false) => query.push(" AND cover_image IS NULL"),
+        None => query,
+    };
+}
+
+fn post_from_row(row: &PgRow) -> Result<BlogPost> {
+    Ok(BlogPost {
+        id: row.get("id"),
+        title: row.get("title"),
+        slug: row.get("slug"),
+        published_at: row.get("published_at"),
+        cover_image: row.get("cover_image"),
+        tags: row.get("tags"),
+        series: row.get("series"),
+        series_position: row.get("series_position"),
+        components: components_from_row(row)?,
+        author_id: row.get("author_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/// Parse the `components` column, upgrading it from the row's
+/// `schema_version` first.
+fn components_from_row(row: &PgRow) -> Result<Vec<BlogComponent>> {
+    let mut components: serde_json::Value = row.get("components");
+    upgrade_components(&mut components, row.get("schema_version"))?;
+    Ok(components_from_json(components)?)
+}
+
+/// An `ILIKE` pattern matching text that contains `text`.
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn source_from_row(row: &PgRow) -> PostSource {
+    PostSource {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        source_path: row.get("source_path"),
+        source_hash: row.get("source_hash"),
+        content_updated_at: row.get("content_updated_at"),
+        source_synced_at: row.get("source_synced_at"),
+    }
+}
+
+fn summary_from_row(row: &PgRow) -> BlogPostSummary {
+    BlogPostSummary {
+        id: row.get("id"),
+        title: row.get("title"),
+        slug: row.get("slug"),
+        published_at: row.get("published_at"),
+        cover_image: row.get("cover_image"),
+        tags: row.get("tags"),
+        author_id: row.get("author_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn featured_from_row(row: &PgRow) -> FeaturedPost {
+    FeaturedPost {
+        post: summary_from_row(row),
+        featured_position: row.get("featured_position"),
+    }
+}
+
+fn user_from_row(row: &PgRow) -> User {
+    User {
+        id: row.get("id"),
+        email: row.get("email"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
 
-        Ok(row.get("count"))
+fn redirect_from_row(row: &PgRow) -> Redirect {
+    Redirect {
+        id: row.get("id"),
+        from_path: row.get("from_path"),
+        to_path: row.get("to_path"),
+        permanent: row.get("permanent"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
     }
 }
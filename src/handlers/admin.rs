@@ -0,0 +1,282 @@
+use crate::config::Config;
+use crate::handlers::error::AppError;
+use crate::models::blog::{AuthorInput, FeaturedInput};
+use crate::models::redirect::RedirectInput;
+use crate::services::blog::BlogService;
+use crate::services::csv_import::{table_from_csv, CsvTableOptions};
+use crate::services::export::{parse_archive, post_to_markdown};
+use crate::services::markdown_import::{post_from_markdown, MarkdownImportOptions};
+use crate::services::redirects::RedirectService;
+use crate::services::validation::{parse_post_input, validate_components, ValidationErrors};
+use crate::templates::TemplateEngine;
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest, HttpResponse};
+use anyhow::Context;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Largest site archive accepted by [`import_archive`].
+pub const ARCHIVE_UPLOAD_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Extractor that only succeeds for requests carrying the configured
+/// `Authorization: Bearer <ADMIN_TOKEN>` header.
+pub struct AdminAuth;
+
+impl FromRequest for AdminAuth {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let expected = req
+            .app_data::<web::Data<Config>>()
+            .and_then(|config| config.admin_token.clone());
+
+        let Some(expected) = expected else {
+            return ready(Err(AppError::not_found("Admin API is disabled")));
+        };
+
+        let provided = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        match provided {
+            Some(token) if token_matches(token, &expected) => ready(Ok(AdminAuth)),
+            _ => ready(Err(AppError::Unauthorized(
+                "Invalid admin token".to_string(),
+            ))),
+        }
+    }
+}
+
+/// Compare digests rather than the tokens themselves: `==` on strings stops
+/// at the first differing byte, so its timing would reveal how much of a
+/// guess is right, while a matching prefix of digests says nothing about
+/// the token.
+fn token_matches(provided: &str, expected: &str) -> bool {
+    Sha256::digest(provided.as_bytes()) == Sha256::digest(expected.as_bytes())
+}
+
+pub async fn create_post(
+    _auth: AdminAuth,
+    blog_service: web::Data<Arc<BlogService>>,
+    body: web::Json<serde_json::Value>,
+) -> Result<HttpResponse, AppError> {
+    let input = parse_post_input(body.into_inner())?;
+    let post = blog_service.create_post(&input).await?;
+    Ok(HttpResponse::Created().json(post))
+}
+
+pub async fn update_post(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    blog_service: web::Data<Arc<BlogService>>,
+    body: web::Json<serde_json::Value>,
+) -> Result<HttpResponse, AppError> {
+    let input = parse_post_input(body.into_inner())?;
+    let post = blog_service
+        .update_post(path.into_inner(), &input)
+        .await?
+        .ok_or_else(|| AppError::not_found("Post not found"))?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+/// Feature a post on the homepage, at `position` if given, or stop
+/// featuring it.
+pub async fn set_featured(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    blog_service: web::Data<Arc<BlogService>>,
+    body: web::Json<FeaturedInput>,
+) -> Result<HttpResponse, AppError> {
+    let post = blog_service
+        .set_featured(path.into_inner(), &body)
+        .await?
+        .ok_or_else(|| AppError::not_found("Post not found"))?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+/// Credit a post to a user, or to nobody with `{"author_id": null}`.
+pub async fn set_author(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    blog_service: web::Data<Arc<BlogService>>,
+    body: web::Json<AuthorInput>,
+) -> Result<HttpResponse, AppError> {
+    let post = blog_service
+        .set_author(path.into_inner(), &body)
+        .await?
+        .ok_or_else(|| AppError::not_found("Post not found"))?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+/// Featured posts in the order the homepage shows them, drafts included.
+pub async fn list_featured(
+    _auth: AdminAuth,
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let posts = blog_service.list_featured().await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "posts": posts })))
+}
+
+/// Render a post, published or not, the way readers will see it. Unlike the
+/// public page, components that fail to deserialize are shown as warnings.
+pub async fn preview_post(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let post = blog_service
+        .get_post_by_id(path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::not_found("Post not found"))?;
+    let html = template_engine.render_blog_post(&post, &[], true)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+/// Create a post from a Markdown article with front matter (request body).
+/// With `?dry_run=true` the converted post is returned without saving it.
+pub async fn import_markdown(
+    _auth: AdminAuth,
+    blog_service: web::Data<Arc<BlogService>>,
+    options: web::Query<MarkdownImportOptions>,
+    body: String,
+) -> Result<HttpResponse, AppError> {
+    let input = post_from_markdown(&body, &options)?;
+
+    if options.dry_run {
+        return Ok(HttpResponse::Ok().json(input));
+    }
+
+    let post = blog_service.create_post(&input).await?;
+    Ok(HttpResponse::Created().json(post))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Download a post as Markdown with front matter or as a standalone HTML page.
+pub async fn export_post(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    query: web::Query<ExportQuery>,
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let post = blog_service
+        .get_post_by_id(path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::not_found("Post not found"))?;
+
+    let (body, content_type, extension) = match query.format {
+        ExportFormat::Markdown => (
+            post_to_markdown(&post),
+            "text/markdown; charset=utf-8",
+            "md",
+        ),
+        ExportFormat::Html => (
+            template_engine.render_standalone_post(&post)?,
+            "text/html; charset=utf-8",
+            "html",
+        ),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header(attachment(&format!("{}.{}", post.slug, extension)))
+        .body(body))
+}
+
+/// Download every post as a site archive (see [`crate::services::export`]).
+pub async fn export_archive(
+    _auth: AdminAuth,
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let archive = blog_service
+        .export_archive()
+        .await
+        .context("Failed to export archive")?;
+    let filename = format!(
+        "ruststack-archive-{}.json",
+        archive.exported_at.format("%Y%m%d")
+    );
+    Ok(HttpResponse::Ok()
+        .insert_header(attachment(&filename))
+        .json(archive))
+}
+
+/// Restore a site archive (request body): its posts, featured posts, old
+/// slugs and redirects.
+pub async fn import_archive(
+    _auth: AdminAuth,
+    blog_service: web::Data<Arc<BlogService>>,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let archive = serde_json::from_slice(&body)
+        .map_err(|e| ValidationErrors::single("$", e.to_string()))
+        .and_then(parse_archive)?;
+    let imported = blog_service.import_archive(&archive).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "imported": imported })))
+}
+
+fn attachment(filename: &str) -> header::ContentDisposition {
+    header::ContentDisposition {
+        disposition: header::DispositionType::Attachment,
+        parameters: vec![header::DispositionParam::Filename(filename.to_string())],
+    }
+}
+
+/// Turn an uploaded CSV file (request body) into a `table` component that
+/// can be inserted into a post.
+pub async fn import_csv_table(
+    _auth: AdminAuth,
+    options: web::Query<CsvTableOptions>,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let component = table_from_csv(&body, &options)?;
+    validate_components(std::slice::from_ref(&component))?;
+    Ok(HttpResponse::Ok().json(component))
+}
+
+pub async fn list_redirects(
+    _auth: AdminAuth,
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
+    let redirects = redirect_service.list().await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "redirects": redirects })))
+}
+
+pub async fn create_redirect(
+    _auth: AdminAuth,
+    redirect_service: web::Data<Arc<RedirectService>>,
+    body: web::Json<RedirectInput>,
+) -> Result<HttpResponse, AppError> {
+    let redirect = redirect_service.create(&body).await?;
+    Ok(HttpResponse::Created().json(redirect))
+}
+
+pub async fn delete_redirect(
+    _auth: AdminAuth,
+    path: web::Path<Uuid>,
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
+    if !redirect_service.delete(path.into_inner()).await? {
+        return Err(AppError::not_found("Redirect not found"));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,62 @@
+//! The public JSON API, one module per version, each mounted under its own
+//! prefix by `main.rs` with its `routes`.
+//!
+//! A version is a contract: its response types, and the OpenAPI document
+//! generated from them, only change compatibly. A breaking change, like a
+//! new component format, goes into a new `vN` module serving its own types
+//! built from the models, while older versions keep serializing what they
+//! always did, until their sunset.
+//!
+//! The unversioned `/api/...` paths the API started out on answer like
+//! `v1`, with headers announcing their retirement (see [`deprecated`]).
+
+pub mod v1;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue, LINK};
+use actix_web::middleware::Next;
+
+/// Where the unversioned paths are mounted.
+pub const LEGACY_PREFIX: &str = "/api";
+
+/// When the unversioned paths were deprecated, 2026-10-19, as a Unix time.
+const LEGACY_DEPRECATED_AT: i64 = 1_792_368_000;
+
+/// When the unversioned paths stop answering.
+const LEGACY_SUNSET: &str = "Mon, 19 Apr 2027 00:00:00 GMT";
+
+/// Middleware for the unversioned paths: marks every response of their
+/// routes, errors included, as deprecated (RFC 9745) with a sunset date
+/// (RFC 8594), and links to the same resource under [`v1::PREFIX`].
+pub async fn deprecated(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let successor = req
+        .path()
+        .strip_prefix(LEGACY_PREFIX)
+        .map(|rest| format!("{}{}", v1::PREFIX, rest));
+
+    let mut res = next.call(req).await?;
+    // Paths that were never part of the API have no successor.
+    if res.request().match_pattern().is_none() {
+        return Ok(res);
+    }
+    let headers = res.headers_mut();
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_str(&format!("@{}", LEGACY_DEPRECATED_AT))?,
+    );
+    headers.insert(
+        HeaderName::from_static("sunset"),
+        HeaderValue::from_static(LEGACY_SUNSET),
+    );
+    if let Some(successor) = successor {
+        headers.insert(
+            LINK,
+            HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))?,
+        );
+    }
+    Ok(res)
+}
@@ -0,0 +1,128 @@
+//! Version 1 of the public API, under [`PREFIX`]. Posts and their
+//! components are returned as the models serialize them.
+
+pub mod openapi;
+
+use crate::handlers::error::AppError;
+use crate::handlers::moved_post;
+use crate::models::blog::{ArchiveYear, BlogComponent, BlogPostSummary};
+use crate::models::listing::PostListQuery;
+use crate::services::blog::BlogService;
+use crate::services::redirects::RedirectService;
+use actix_web::{web, HttpRequest, HttpResponse};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Arc;
+
+pub const PREFIX: &str = "/api/v1";
+
+/// Register the routes of this version on a scope mounted at [`PREFIX`] (or
+/// at the deprecated unversioned prefix).
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/blog/archive", web::get().to(get_archive))
+        .route("/blog/{slug}", web::get().to(get_blog_post))
+        .route("/blog/{slug}/related", web::get().to(get_related_posts))
+        .route("/blog", web::get().to(list_blog_posts))
+        .route("/schema/components", web::get().to(component_schema))
+        .route("/openapi.json", web::get().to(openapi::openapi_json))
+        .route("/docs", web::get().to(openapi::api_docs));
+}
+
+/// Body of [`list_blog_posts`].
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PostList {
+    pub posts: Vec<BlogPostSummary>,
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Pagination {
+    pub per_page: usize,
+    /// Pass back as `cursor`, with the same `sort` and filters, for the next
+    /// page; `null` on the last one.
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// Body of [`get_archive`].
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Archive {
+    /// Newest first.
+    pub years: Vec<ArchiveYear>,
+}
+
+/// Body of [`get_related_posts`].
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RelatedPosts {
+    /// Most related first.
+    pub posts: Vec<BlogPostSummary>,
+}
+
+/// A page of published posts, filtered and sorted as `query` asks. The
+/// next page is fetched by passing back `pagination.next_cursor`.
+pub async fn list_blog_posts(
+    blog_service: web::Data<Arc<BlogService>>,
+    query: web::Query<PostListQuery>,
+) -> Result<HttpResponse, AppError> {
+    let page = blog_service.list_posts_page(&query).await?;
+    Ok(HttpResponse::Ok().json(PostList {
+        posts: page.posts,
+        pagination: Pagination {
+            per_page: page.per_page,
+            has_more: page.next_cursor.is_some(),
+            next_cursor: page.next_cursor,
+        },
+    }))
+}
+
+/// Post counts per year and month, newest first.
+pub async fn get_archive(
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let years = blog_service.archive().await?;
+    Ok(HttpResponse::Ok().json(Archive { years }))
+}
+
+pub async fn get_blog_post(
+    req: HttpRequest,
+    path: web::Path<String>,
+    blog_service: web::Data<Arc<BlogService>>,
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
+    let slug = path.into_inner();
+
+    let Some(post) = blog_service.get_post_by_slug(&slug).await? else {
+        let moved = moved_post(&req, &blog_service, &redirect_service, &slug, |slug| {
+            format!("{}/blog/{}", PREFIX, slug)
+        })
+        .await;
+        return moved.ok_or_else(|| AppError::not_found("Post not found"));
+    };
+
+    Ok(HttpResponse::Ok().json(post))
+}
+
+pub async fn get_related_posts(
+    req: HttpRequest,
+    path: web::Path<String>,
+    blog_service: web::Data<Arc<BlogService>>,
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
+    let slug = path.into_inner();
+
+    let Some(post) = blog_service.get_post_by_slug(&slug).await? else {
+        let moved = moved_post(&req, &blog_service, &redirect_service, &slug, |slug| {
+            format!("{}/blog/{}/related", PREFIX, slug)
+        })
+        .await;
+        return moved.ok_or_else(|| AppError::not_found("Post not found"));
+    };
+
+    let posts = blog_service.related_posts(&post).await?;
+    Ok(HttpResponse::Ok().json(RelatedPosts { posts }))
+}
+
+/// JSON Schema describing a single entry of a post's `components` array.
+pub async fn component_schema() -> HttpResponse {
+    HttpResponse::Ok().json(schemars::schema_for!(BlogComponent))
+}
@@ -0,0 +1,305 @@
+//! The OpenAPI 3.1 document describing version 1 of the public API, served
+//! at `/api/v1/openapi.json` and browsable at `/api/v1/docs`. Schemas are
+//! generated from the types the handlers return and accept, through the same
+//! `JsonSchema` derives as `/api/v1/schema/components`, so they can't drift
+//! from the code; only the list of operations is written out here.
+
+use crate::handlers::api::v1::{Archive, PostList, RelatedPosts, PREFIX};
+use crate::handlers::error::{AppError, Problem, PROBLEM_JSON};
+use crate::models::blog::BlogPost;
+use crate::models::listing::PostListQuery;
+use crate::templates::TemplateEngine;
+use actix_web::{web, HttpResponse};
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+const SCHEMAS_PATH: &str = "/components/schemas";
+
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(document())
+}
+
+/// Swagger UI for [`document`], served from `/static/vendor/swagger-ui`.
+pub async fn api_docs(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+) -> Result<HttpResponse, AppError> {
+    let html = template_engine.render_api_docs(&format!("{}/openapi.json", PREFIX))?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+pub fn document() -> Value {
+    let mut generator = SchemaSettings::draft2020_12()
+        .with(|settings| {
+            settings.definitions_path = SCHEMAS_PATH.into();
+            settings.meta_schema = None;
+        })
+        .for_serialize()
+        .into_generator();
+
+    let slug = json!({
+        "name": "slug",
+        "in": "path",
+        "required": true,
+        "schema": { "type": "string" },
+    });
+    let moved = json!({
+        "description": "The post was renamed; `Location` is its current URL",
+        "headers": { "Location": { "schema": { "type": "string" } } },
+    });
+
+    let paths = json!({
+        "/blog": {
+            "get": {
+                "operationId": "listPosts",
+                "summary": "List published posts",
+                "description": "A page of published posts, newest first unless `sort=oldest`. \
+                                Fetch the next page by passing `pagination.next_cursor` back as \
+                                `cursor` with the same `sort` and filters.",
+                "parameters": query_parameters::<PostListQuery>(&mut generator),
+                "responses": {
+                    "200": json_response("A page of posts", generator.subschema_for::<PostList>()),
+                    "400": { "$ref": "#/components/responses/BadRequest" },
+                    "422": { "$ref": "#/components/responses/ValidationFailed" },
+                },
+            },
+        },
+        "/blog/archive": {
+            "get": {
+                "operationId": "getArchive",
+                "summary": "Count published posts per year and month (UTC)",
+                "responses": {
+                    "200": json_response("Post counts", generator.subschema_for::<Archive>()),
+                },
+            },
+        },
+        "/blog/{slug}": {
+            "get": {
+                "operationId": "getPost",
+                "summary": "Get a published post",
+                "parameters": [slug],
+                "responses": {
+                    "200": json_response("The post", generator.subschema_for::<BlogPost>()),
+                    "301": moved,
+                    "404": { "$ref": "#/components/responses/NotFound" },
+                },
+            },
+        },
+        "/blog/{slug}/related": {
+            "get": {
+                "operationId": "getRelatedPosts",
+                "summary": "List posts related to a post",
+                "description": "Ranked by shared tags, series and content similarity.",
+                "parameters": [slug],
+                "responses": {
+                    "200": json_response(
+                        "Related posts",
+                        generator.subschema_for::<RelatedPosts>(),
+                    ),
+                    "301": moved,
+                    "404": { "$ref": "#/components/responses/NotFound" },
+                },
+            },
+        },
+        "/schema/components": {
+            "get": {
+                "operationId": "getComponentSchema",
+                "summary": "Get the JSON Schema of a post component",
+                "responses": {
+                    "200": json_response(
+                        "A JSON Schema (2020-12) document",
+                        json!({ "type": "object" }),
+                    ),
+                },
+            },
+        },
+        "/openapi.json": {
+            "get": {
+                "operationId": "getOpenApi",
+                "summary": "Get this document",
+                "responses": {
+                    "200": json_response(
+                        "An OpenAPI 3.1 document",
+                        json!({ "type": "object" }),
+                    ),
+                },
+            },
+        },
+    });
+
+    let problem = generator.subschema_for::<Problem>();
+    let problem_response = |description: &str| {
+        json!({
+            "description": description,
+            "content": { (PROBLEM_JSON): { "schema": problem } },
+        })
+    };
+    let responses = json!({
+        "BadRequest": problem_response("A query parameter has the wrong type"),
+        "NotFound": problem_response("There is no such post"),
+        "ValidationFailed": problem_response("The request is invalid; `errors` says why"),
+    });
+
+    let mut schemas = generator.take_definitions(true);
+    split_tagged_union(&mut schemas, "BlogComponent", "type", "Component");
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Ruststack API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Published blog posts and their taxonomy. Errors are RFC 9457 \
+                            `application/problem+json` documents.",
+        },
+        "servers": [{ "url": PREFIX }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "responses": responses,
+        },
+    })
+}
+
+fn json_response(description: &str, schema: impl Serialize) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+/// One optional query parameter per field of `T`, described by its doc
+/// comment.
+fn query_parameters<T: JsonSchema>(generator: &mut SchemaGenerator) -> Vec<Value> {
+    let schema = T::json_schema(generator);
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    properties
+        .iter()
+        .map(|(name, schema)| {
+            let mut schema = schema.clone();
+            let description = schema
+                .as_object_mut()
+                .and_then(|schema| schema.remove("description"));
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "schema": without_null(schema),
+            });
+            if let Some(description) = description {
+                parameter["description"] = description;
+            }
+            parameter
+        })
+        .collect()
+}
+
+/// A query parameter is omitted rather than `null`, so drop `null` from the
+/// types an `Option` field allows.
+fn without_null(mut schema: Value) -> Value {
+    if let Some(types) = schema.get_mut("type").and_then(Value::as_array_mut) {
+        types.retain(|t| t != "null");
+        if types.len() == 1 {
+            schema["type"] = types.remove(0);
+        }
+    }
+    schema
+}
+
+/// Move each variant of the internally tagged enum `name` into a schema of
+/// its own, e.g. `HeadingComponent` for `{"type": "heading"}`, so that a
+/// discriminator on `tag` can map every tag to its schema.
+fn split_tagged_union(schemas: &mut Map<String, Value>, name: &str, tag: &str, suffix: &str) {
+    let Some(variants) = schemas
+        .get_mut(name)
+        .and_then(|schema| schema.get_mut("oneOf"))
+        .and_then(Value::as_array_mut)
+        .map(std::mem::take)
+    else {
+        return;
+    };
+
+    let mut one_of = Vec::new();
+    let mut mapping = Map::new();
+    for variant in variants {
+        let Some(value) = variant
+            .pointer(&format!("/properties/{}/const", tag))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            one_of.push(variant);
+            continue;
+        };
+        let variant_name = format!("{}{}", pascal_case(&value), suffix);
+        let reference = format!("#{}/{}", SCHEMAS_PATH, variant_name);
+        one_of.push(json!({ "$ref": reference }));
+        mapping.insert(value, Value::String(reference));
+        schemas.insert(variant_name, variant);
+    }
+
+    let schema = &mut schemas[name];
+    schema["oneOf"] = Value::Array(one_of);
+    schema["discriminator"] = json!({ "propertyName": tag, "mapping": mapping });
+}
+
+fn pascal_case(text: &str) -> String {
+    text.split(['_', '-'])
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::String(reference)) = object.get("$ref") {
+                    found.push(reference.clone());
+                }
+                object.values().for_each(|value| references(value, found));
+            }
+            Value::Array(array) => array.iter().for_each(|value| references(value, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn every_reference_resolves() {
+        let document = document();
+        let mut found = Vec::new();
+        references(&document, &mut found);
+        assert!(!found.is_empty());
+        for reference in found {
+            let pointer = reference.strip_prefix('#').expect("a local reference");
+            assert!(document.pointer(pointer).is_some(), "{}", reference);
+        }
+    }
+
+    #[test]
+    fn components_have_a_discriminator() {
+        let document = document();
+        let component = &document["components"]["schemas"]["BlogComponent"];
+        assert_eq!(component["discriminator"]["propertyName"], "type");
+        assert_eq!(
+            component["discriminator"]["mapping"]["heading"],
+            "#/components/schemas/HeadingComponent"
+        );
+        assert_eq!(
+            component["oneOf"].as_array().map(Vec::len),
+            component["discriminator"]["mapping"]
+                .as_object()
+                .map(Map::len)
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Errors returned by handlers. Each maps to a status code and is rendered by
+//! [`render_errors`] as an HTML page for readers or as an RFC 9457
+//! `application/problem+json` document for the API and clients asking for
+//! JSON.
+
+use crate::models::blog::BlogPostSummary;
+use crate::services::blog::{BlogService, SUGGESTED_POSTS_LIMIT};
+use crate::services::slugs::slugify;
+use crate::services::validation::{ValidationErrors, ValidationIssue};
+use crate::templates::TemplateEngine;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{mime, web, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+use tracing_actix_web::RequestId;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// All clients learn about a server error.
+const INTERNAL_DETAIL: &str = "Something went wrong on our side";
+
+#[derive(Debug)]
+pub enum AppError {
+    /// The message names what is missing, e.g. "Post not found".
+    NotFound(String),
+    Validation(ValidationErrors),
+    Unauthorized(String),
+    Conflict(String),
+    /// Anything else. The cause is logged, never shown.
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    /// What clients are told. Internal errors stay vague.
+    pub fn detail(&self) -> String {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Unauthorized(message)
+            | AppError::Conflict(message) => message.clone(),
+            AppError::Validation(errors) => errors.to_string(),
+            AppError::Internal(_) => INTERNAL_DETAIL.to_string(),
+        }
+    }
+
+    fn validation(&self) -> Option<&ValidationErrors> {
+        match self {
+            AppError::Validation(errors) => Some(errors),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Internal(error) => write!(f, "{:#}", error),
+            _ => write!(f, "{}", self.detail()),
+        }
+    }
+}
+
+/// Services report invalid input as [`ValidationErrors`] inside their
+/// `anyhow::Error`, and a save that breaks a unique constraint no service
+/// anticipated is a conflict.
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<ValidationErrors>() {
+            Ok(errors) => return AppError::Validation(errors),
+            Err(error) => error,
+        };
+        let unique_violation = error
+            .downcast_ref::<sqlx::Error>()
+            .and_then(|error| error.as_database_error())
+            .is_some_and(|error| error.is_unique_violation());
+        if unique_violation {
+            return AppError::Conflict("It already exists".to_string());
+        }
+        AppError::Internal(error)
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors)
+    }
+}
+
+impl From<minijinja::Error> for AppError {
+    fn from(error: minijinja::Error) -> Self {
+        AppError::Internal(anyhow::Error::new(error).context("Failed to render template"))
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The problem document; [`render_errors`] swaps it for an HTML page
+    /// where that is wanted.
+    fn error_response(&self) -> HttpResponse {
+        problem_response(self.status_code(), &self.detail(), self.validation(), None)
+    }
+}
+
+/// Middleware rendering every error response, from handlers and extractors
+/// alike, in the format the client wants, and logging it with the request
+/// ID. Must be wrapped inside `TracingLogger`, which assigns the ID.
+pub async fn render_errors(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let res = next.call(req).await?;
+    let Some(error) = res.response().error() else {
+        return Ok(res.map_into_boxed_body());
+    };
+
+    let status = res.status();
+    let app_error = error.as_error::<AppError>();
+    let request_id = res.request().extensions().get::<RequestId>().copied();
+    // Logged inside the request's root span, which records its ID.
+    if status.is_server_error() {
+        tracing::error!(status = status.as_u16(), "{:?}", error);
+    } else {
+        tracing::info!(status = status.as_u16(), "{}", error);
+    }
+
+    let detail = match app_error {
+        Some(app_error) => app_error.detail(),
+        None if status.is_server_error() => INTERNAL_DETAIL.to_string(),
+        None => error.to_string(),
+    };
+    let validation = app_error.and_then(AppError::validation);
+
+    let mut response = if wants_json(res.request()) {
+        problem_response(status, &detail, validation, request_id)
+    } else {
+        html_response(res.request(), status, &detail, request_id).await
+    };
+    // Keep headers added on the way out, e.g. `Deprecation`, or `Allow`.
+    let rendered: Vec<_> = response.headers().keys().cloned().collect();
+    for (name, value) in res.headers() {
+        if !rendered.contains(name) && name != header::CONTENT_LENGTH {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    Ok(res.into_response(response))
+}
+
+/// The API always answers JSON; pages do when JSON is preferred over HTML.
+fn wants_json(req: &HttpRequest) -> bool {
+    if req.path() == "/api" || req.path().starts_with("/api/") || req.path() == "/graphql" {
+        return true;
+    }
+    req.get_header::<header::Accept>()
+        .and_then(|accept| accept.ranked().into_iter().next())
+        .is_some_and(|mime| mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+}
+
+/// An RFC 9457 problem document, as every API error is answered.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Problem {
+    /// Always `about:blank`: the status says it all.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// The reason phrase of the status, e.g. "Not Found".
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Every problem found, for validation failures (`422`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<ValidationIssue>>,
+    /// Quote it when reporting a server error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+fn problem_response(
+    status: StatusCode,
+    detail: &str,
+    validation: Option<&ValidationErrors>,
+    request_id: Option<RequestId>,
+) -> HttpResponse {
+    let problem = Problem {
+        problem_type: "about:blank".to_string(),
+        title: status.canonical_reason().unwrap_or("Error").to_string(),
+        status: status.as_u16(),
+        detail: detail.to_string(),
+        errors: validation.map(|validation| validation.errors.clone()),
+        request_id: request_id.map(|id| id.to_string()),
+    };
+
+    HttpResponse::build(status)
+        .content_type(PROBLEM_JSON)
+        .json(problem)
+}
+
+async fn html_response(
+    req: &HttpRequest,
+    status: StatusCode,
+    detail: &str,
+    request_id: Option<RequestId>,
+) -> HttpResponse {
+    let Some(engine) = req.app_data::<web::Data<Arc<TemplateEngine>>>() else {
+        return HttpResponse::build(status).body(detail.to_string());
+    };
+    let request_id = request_id.map(|id| id.to_string());
+
+    let html = if status == StatusCode::NOT_FOUND {
+        let (query, suggestions) = suggestions(req).await;
+        engine.render_not_found(detail, &query, &suggestions, request_id)
+    } else {
+        engine.render_error(
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Error"),
+            detail,
+            request_id,
+        )
+    };
+
+    match html {
+        Ok(html) => HttpResponse::build(status)
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(error) => {
+            tracing::error!("Failed to render error page: {:?}", error);
+            HttpResponse::build(status).body(detail.to_string())
+        }
+    }
+}
+
+/// Search terms taken from the last segment of a path that led nowhere,
+/// and the posts with the most similar slugs. Failing to find any is not
+/// worth more than a warning.
+///
+/// Only pages asked for with `GET` get suggestions: a missing asset (under
+/// `/static` or any file name with an extension, which slugs never have)
+/// or a form posted to the wrong place is not worth a database query.
+async fn suggestions(req: &HttpRequest) -> (String, Vec<BlogPostSummary>) {
+    let segment = req.path().rsplit('/').find(|s| !s.is_empty()).unwrap_or("");
+    let is_asset =
+        req.path() == "/static" || req.path().starts_with("/static/") || segment.contains('.');
+    if segment.is_empty() || is_asset || req.method() != Method::GET {
+        return (String::new(), Vec::new());
+    }
+    let slug = slugify(segment);
+    let query = slug.replace('-', " ");
+
+    let Some(blog_service) = req.app_data::<web::Data<Arc<BlogService>>>() else {
+        return (query, Vec::new());
+    };
+    match blog_service
+        .similar_posts(&slug, SUGGESTED_POSTS_LIMIT)
+        .await
+    {
+        Ok(posts) => (query, posts),
+        Err(error) => {
+            tracing::warn!(slug = %slug, "Failed to find similar posts: {:?}", error);
+            (query, Vec::new())
+        }
+    }
+}
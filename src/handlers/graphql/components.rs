@@ -0,0 +1,280 @@
+//! Blog components as the `BlogComponent` union, one object type per
+//! component `type`, named like the schemas of the OpenAPI document
+//! (`HeadingComponent`, ...). Clients select the fields of each with inline
+//! fragments. Components that failed to deserialize are left out.
+
+use crate::models::blog::{self, BlogComponent};
+use async_graphql::{Enum, SimpleObject, Union};
+
+#[derive(Union)]
+#[graphql(name = "BlogComponent")]
+pub enum Component {
+    Heading(HeadingComponent),
+    Paragraph(ParagraphComponent),
+    Code(CodeComponent),
+    Callout(CalloutComponent),
+    Card(CardComponent),
+    Image(ImageComponent),
+    Quote(QuoteComponent),
+    Tabs(TabsComponent),
+    Details(DetailsComponent),
+    Steps(StepsComponent),
+    Math(MathComponent),
+    Diagram(DiagramComponent),
+    Video(VideoComponent),
+    Embed(EmbedComponent),
+    Table(TableComponent),
+}
+
+/// The known components among `components`, in order.
+pub fn components(components: &[BlogComponent]) -> Vec<Component> {
+    components
+        .iter()
+        .filter_map(Component::from_model)
+        .collect()
+}
+
+impl Component {
+    fn from_model(component: &BlogComponent) -> Option<Self> {
+        Some(match component.clone() {
+            BlogComponent::Heading { text } => Component::Heading(HeadingComponent { text }),
+            BlogComponent::Paragraph { markdown } => {
+                Component::Paragraph(ParagraphComponent { markdown })
+            }
+            BlogComponent::Code { language, code } => {
+                Component::Code(CodeComponent { language, code })
+            }
+            BlogComponent::Callout { style, markdown } => {
+                Component::Callout(CalloutComponent { style, markdown })
+            }
+            BlogComponent::Card {
+                title,
+                description,
+                link,
+            } => Component::Card(CardComponent {
+                title,
+                description,
+                link,
+            }),
+            BlogComponent::Image { src, alt, caption } => {
+                Component::Image(ImageComponent { src, alt, caption })
+            }
+            BlogComponent::Quote { text, author } => {
+                Component::Quote(QuoteComponent { text, author })
+            }
+            BlogComponent::Tabs { tabs } => Component::Tabs(TabsComponent {
+                tabs: tabs
+                    .iter()
+                    .map(|tab| Tab {
+                        label: tab.label.clone(),
+                        components: self::components(&tab.components),
+                    })
+                    .collect(),
+            }),
+            BlogComponent::Details {
+                summary,
+                markdown,
+                components,
+                open,
+            } => Component::Details(DetailsComponent {
+                summary,
+                markdown,
+                components: self::components(&components),
+                open,
+            }),
+            BlogComponent::Steps { steps } => Component::Steps(StepsComponent {
+                steps: steps
+                    .iter()
+                    .map(|step| Step {
+                        title: step.title.clone(),
+                        markdown: step.markdown.clone(),
+                        components: self::components(&step.components),
+                    })
+                    .collect(),
+            }),
+            BlogComponent::Math { latex } => Component::Math(MathComponent { latex }),
+            BlogComponent::Diagram { source, caption } => {
+                Component::Diagram(DiagramComponent { source, caption })
+            }
+            BlogComponent::Video {
+                src,
+                poster,
+                captions,
+            } => Component::Video(VideoComponent {
+                src,
+                poster,
+                captions: captions
+                    .into_iter()
+                    .map(|track| CaptionTrack {
+                        src: track.src,
+                        srclang: track.srclang,
+                        label: track.label,
+                        default: track.default,
+                    })
+                    .collect(),
+            }),
+            BlogComponent::Embed {
+                provider,
+                url,
+                title,
+            } => Component::Embed(EmbedComponent {
+                provider: provider.into(),
+                url,
+                title,
+            }),
+            BlogComponent::Table {
+                headers,
+                rows,
+                alignment,
+                caption,
+                sortable,
+            } => Component::Table(TableComponent {
+                headers,
+                rows,
+                alignment: alignment.into_iter().map(Into::into).collect(),
+                caption,
+                sortable,
+            }),
+            BlogComponent::Unknown(_) => return None,
+        })
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct HeadingComponent {
+    pub text: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ParagraphComponent {
+    pub markdown: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CodeComponent {
+    pub language: String,
+    pub code: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CalloutComponent {
+    /// One of `info`, `warning`, `success`, `error` and `note`.
+    pub style: String,
+    pub markdown: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CardComponent {
+    pub title: String,
+    pub description: String,
+    pub link: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ImageComponent {
+    pub src: String,
+    pub alt: String,
+    pub caption: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct QuoteComponent {
+    pub text: String,
+    pub author: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct TabsComponent {
+    pub tabs: Vec<Tab>,
+}
+
+#[derive(SimpleObject)]
+pub struct Tab {
+    pub label: String,
+    pub components: Vec<Component>,
+}
+
+#[derive(SimpleObject)]
+pub struct DetailsComponent {
+    pub summary: String,
+    pub markdown: Option<String>,
+    pub components: Vec<Component>,
+    pub open: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct StepsComponent {
+    pub steps: Vec<Step>,
+}
+
+#[derive(SimpleObject)]
+pub struct Step {
+    pub title: String,
+    pub markdown: Option<String>,
+    pub components: Vec<Component>,
+}
+
+#[derive(SimpleObject)]
+pub struct MathComponent {
+    pub latex: String,
+}
+
+/// A diagram in the Graphviz DOT language.
+#[derive(SimpleObject)]
+pub struct DiagramComponent {
+    pub source: String,
+    pub caption: Option<String>,
+}
+
+/// A self-hosted video file.
+#[derive(SimpleObject)]
+pub struct VideoComponent {
+    pub src: String,
+    pub poster: Option<String>,
+    pub captions: Vec<CaptionTrack>,
+}
+
+#[derive(SimpleObject)]
+pub struct CaptionTrack {
+    pub src: String,
+    pub srclang: String,
+    pub label: String,
+    pub default: bool,
+}
+
+/// Third-party content, rendered as a click-to-load facade.
+#[derive(SimpleObject)]
+pub struct EmbedComponent {
+    pub provider: EmbedProvider,
+    pub url: String,
+    pub title: Option<String>,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "blog::EmbedProvider")]
+pub enum EmbedProvider {
+    #[graphql(name = "YOUTUBE")]
+    YouTube,
+    Vimeo,
+    Gist,
+    #[graphql(name = "CODEPEN")]
+    CodePen,
+}
+
+#[derive(SimpleObject)]
+pub struct TableComponent {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Per-column alignment; missing entries default to left.
+    pub alignment: Vec<ColumnAlignment>,
+    pub caption: Option<String>,
+    pub sortable: bool,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "blog::ColumnAlignment")]
+pub enum ColumnAlignment {
+    Left,
+    Center,
+    Right,
+}
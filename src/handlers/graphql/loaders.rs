@@ -0,0 +1,167 @@
+//! Batched lookups for the fields of many parents at once. Each key type
+//! names what is looked up, so one [`DataLoader`] serves them all.
+//!
+//! [`DataLoader`]: async_graphql::dataloader::DataLoader
+
+use super::graphql_error;
+use crate::database::Database;
+use crate::models::blog::{BlogPost, BlogPostSummary};
+use crate::models::listing::{PostCursor, PostOwner, PostPage, PostSort};
+use crate::models::user::User;
+use crate::services::blog::BlogService;
+use async_graphql::dataloader::Loader;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A user credited with a published post, by ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuthorId(pub Uuid);
+
+/// A published post with its components, by ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PostId(pub Uuid);
+
+/// The published posts of a series, in reading order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeriesName(pub String);
+
+/// The posts related to a post, most related first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RelatedTo(pub Uuid);
+
+/// A page of the published posts of an author or with a tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PostsOf {
+    pub owner: PostOwner,
+    pub per_page: usize,
+    pub sort: PostSort,
+    pub after: Option<PostCursor>,
+}
+
+pub struct Loaders {
+    db: Database,
+    blog_service: Arc<BlogService>,
+}
+
+impl Loaders {
+    pub fn new(db: Database, blog_service: Arc<BlogService>) -> Self {
+        Self { db, blog_service }
+    }
+}
+
+impl Loader<AuthorId> for Loaders {
+    type Value = User;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[AuthorId]) -> Result<HashMap<AuthorId, User>, Self::Error> {
+        let ids: Vec<Uuid> = keys.iter().map(|key| key.0).collect();
+        let users = self
+            .db
+            .get_authors_by_ids(&ids)
+            .await
+            .map_err(graphql_error)?;
+        Ok(users
+            .into_iter()
+            .map(|user| (AuthorId(user.id), user))
+            .collect())
+    }
+}
+
+impl Loader<PostId> for Loaders {
+    type Value = BlogPost;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[PostId]) -> Result<HashMap<PostId, BlogPost>, Self::Error> {
+        let ids: Vec<Uuid> = keys.iter().map(|key| key.0).collect();
+        let posts = self
+            .db
+            .get_blog_posts_by_ids(&ids)
+            .await
+            .map_err(graphql_error)?;
+        Ok(posts
+            .into_iter()
+            .map(|post| (PostId(post.id), post))
+            .collect())
+    }
+}
+
+impl Loader<SeriesName> for Loaders {
+    type Value = Vec<BlogPostSummary>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[SeriesName],
+    ) -> Result<HashMap<SeriesName, Vec<BlogPostSummary>>, Self::Error> {
+        let names: Vec<String> = keys.iter().map(|key| key.0.clone()).collect();
+        let posts = self
+            .db
+            .list_series_posts(&names)
+            .await
+            .map_err(graphql_error)?;
+
+        let mut series: HashMap<SeriesName, Vec<BlogPostSummary>> = HashMap::new();
+        for (name, post) in posts {
+            series.entry(SeriesName(name)).or_default().push(post);
+        }
+        Ok(series)
+    }
+}
+
+impl Loader<RelatedTo> for Loaders {
+    type Value = Vec<BlogPostSummary>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[RelatedTo],
+    ) -> Result<HashMap<RelatedTo, Vec<BlogPostSummary>>, Self::Error> {
+        let ids: Vec<Uuid> = keys.iter().map(|key| key.0).collect();
+        let related = self
+            .blog_service
+            .related_posts_for(&ids)
+            .await
+            .map_err(graphql_error)?;
+        Ok(related
+            .into_iter()
+            .map(|(id, posts)| (RelatedTo(id), posts))
+            .collect())
+    }
+}
+
+impl Loader<PostsOf> for Loaders {
+    type Value = PostPage;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[PostsOf]) -> Result<HashMap<PostsOf, PostPage>, Self::Error> {
+        // Parents asking for the same page of their posts share a query.
+        let mut owners: HashMap<(usize, PostSort, Option<PostCursor>), Vec<PostOwner>> =
+            HashMap::new();
+        for key in keys {
+            owners
+                .entry((key.per_page, key.sort, key.after))
+                .or_default()
+                .push(key.owner.clone());
+        }
+
+        let mut pages = HashMap::new();
+        for ((per_page, sort, after), owners) in owners {
+            let owned = self
+                .blog_service
+                .list_post_pages(&owners, sort, after.as_ref(), per_page)
+                .await
+                .map_err(graphql_error)?;
+            pages.extend(owned.into_iter().map(|(owner, page)| {
+                let key = PostsOf {
+                    owner,
+                    per_page,
+                    sort,
+                    after,
+                };
+                (key, page)
+            }));
+        }
+        Ok(pages)
+    }
+}
@@ -0,0 +1,152 @@
+//! The GraphQL endpoint, `POST /graphql`: posts, their authors, series,
+//! related posts and components, tags and series, in one round trip.
+//!
+//! Fields that fetch more data per parent, like a post's author or series,
+//! go through the [`DataLoader`] of the request, which collects the keys
+//! every parent asks for in one tick and fetches them with one query (see
+//! [`loaders`]). A page of twenty posts with their authors and related posts
+//! is thus a handful of queries, not sixty. The same goes for the `posts` of
+//! tags and authors.
+//!
+//! Queries are limited in depth and complexity before they run. Lists of
+//! posts count as many times as they can hold posts: `first` for
+//! connections, [`RELATED_POSTS_LIMIT`] for related posts and
+//! [`types::SERIES_PARTS`] for series.
+//!
+//! [`RELATED_POSTS_LIMIT`]: crate::services::blog::RELATED_POSTS_LIMIT
+
+pub mod components;
+pub mod loaders;
+pub mod query;
+pub mod types;
+
+use crate::database::Database;
+use crate::handlers::error::AppError;
+use crate::services::blog::BlogService;
+use crate::services::validation::ValidationErrors;
+use actix_web::{web, HttpResponse, ResponseError};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{
+    BatchRequest, EmptyMutation, EmptySubscription, ErrorExtensions, Schema, SchemaBuilder,
+};
+use loaders::Loaders;
+use query::Query;
+use std::sync::Arc;
+
+/// Deepest selection a query may make, counting the operation itself.
+pub const MAX_DEPTH: usize = 12;
+
+/// Most fields a query may resolve, lists counted by what they can hold.
+pub const MAX_COMPLEXITY: usize = 2_000;
+
+/// Most operations in one batched request.
+pub const MAX_BATCH: usize = 10;
+
+pub type BlogSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// The schema and what each request's loaders need.
+pub struct GraphqlApi {
+    schema: BlogSchema,
+    db: Database,
+    blog_service: Arc<BlogService>,
+}
+
+impl GraphqlApi {
+    pub fn new(blog_service: Arc<BlogService>, db: Database) -> Self {
+        Self {
+            schema: schema(blog_service.clone()),
+            db,
+            blog_service,
+        }
+    }
+
+    /// A fresh loader for one request, so nothing is shared between them.
+    fn loader(&self) -> DataLoader<Loaders> {
+        DataLoader::new(
+            Loaders::new(self.db.clone(), self.blog_service.clone()),
+            tokio::spawn,
+        )
+    }
+}
+
+pub fn schema(blog_service: Arc<BlogService>) -> BlogSchema {
+    builder().data(blog_service).finish()
+}
+
+fn builder() -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+}
+
+/// Run a GraphQL operation, or a batch of them sent as a JSON array.
+/// Errors of the operations are in the `errors` of their response, with
+/// the HTTP status the REST API would have answered as `extensions.status`.
+pub async fn graphql(
+    api: web::Data<Arc<GraphqlApi>>,
+    request: web::Json<BatchRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut request = request.into_inner();
+    if let BatchRequest::Batch(requests) = &request {
+        if requests.len() > MAX_BATCH {
+            let message = format!("must hold at most {} operations", MAX_BATCH);
+            return Err(ValidationErrors::single("$", message).into());
+        }
+    }
+    for request in request.iter_mut() {
+        request.data.insert(api.loader());
+    }
+
+    let response = api.schema.execute_batch(request).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// A service error as a GraphQL error, telling clients what an API response
+/// would: internal errors are logged and stay vague.
+fn graphql_error(error: anyhow::Error) -> async_graphql::Error {
+    let error = AppError::from(error);
+    let status = error.status_code();
+    if status.is_server_error() {
+        tracing::error!(status = status.as_u16(), "{:?}", error);
+    }
+    async_graphql::Error::new(error.detail()).extend_with(|_, extensions| {
+        extensions.set("status", status.as_u16());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The limits reject a query before any resolver, which would need the
+    /// database, runs.
+    async fn rejection(query: &str) -> String {
+        let response = builder().finish().execute(query).await;
+        assert!(
+            response.data == async_graphql::Value::Null,
+            "{:?}",
+            response
+        );
+        response.errors[0].message.clone()
+    }
+
+    #[actix_web::test]
+    async fn limits_depth_and_complexity() {
+        let deep = format!(
+            "{{ post(slug: \"a\") {{ {} title {} }} }}",
+            "series { previous { ".repeat(MAX_DEPTH / 2),
+            "} }".repeat(MAX_DEPTH / 2)
+        );
+        assert_eq!(rejection(&deep).await, "Query is nested too deep.");
+
+        let wide =
+            "{ posts(first: 100) { edges { node { related { related { title author { name } } } } } } }";
+        assert_eq!(rejection(wide).await, "Query is too complex.");
+    }
+
+    #[test]
+    fn components_are_a_union() {
+        let sdl = builder().finish().sdl();
+        assert!(sdl.contains("union BlogComponent = HeadingComponent | ParagraphComponent"));
+    }
+}
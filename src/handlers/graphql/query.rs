@@ -0,0 +1,86 @@
+use super::graphql_error;
+use super::loaders::{AuthorId, Loaders};
+use super::types::{
+    post_connection, Author, Post, PostConnection, PostFilterInput, PostSort, Series, Tag,
+};
+use crate::models::listing::MAX_PER_PAGE;
+use crate::services::blog::BlogService;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Object, Result};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Most tags or series one query lists.
+const MAX_TAXONOMY: i32 = 100;
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The published post with `slug`. Renamed posts are only found by
+    /// their current slug.
+    async fn post(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Post>> {
+        let post = ctx
+            .data::<Arc<BlogService>>()?
+            .get_post_by_slug(&slug)
+            .await
+            .map_err(graphql_error)?;
+        Ok(post.map(Post::full))
+    }
+
+    /// Published posts, a page at a time: pass the `endCursor` of a page as
+    /// `after` to get the next one.
+    #[graphql(complexity = "first.clamp(1, MAX_PER_PAGE as i32) as usize * child_complexity")]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] first: i32,
+        after: Option<String>,
+        #[graphql(default)] sort: PostSort,
+        #[graphql(default)] filter: PostFilterInput,
+    ) -> Result<PostConnection> {
+        post_connection(ctx, first, after, sort, filter).await
+    }
+
+    /// The user with `id`, if they have published posts.
+    async fn author(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<Author>> {
+        let user = ctx
+            .data::<DataLoader<Loaders>>()?
+            .load_one(AuthorId(id))
+            .await?;
+        Ok(user.map(|user| Author { user }))
+    }
+
+    /// Tags of published posts, the most used first.
+    #[graphql(complexity = "first.clamp(1, MAX_TAXONOMY) as usize * child_complexity")]
+    async fn tags(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20)] first: i32,
+    ) -> Result<Vec<Tag>> {
+        let tags = ctx
+            .data::<Arc<BlogService>>()?
+            .tags(first.clamp(1, MAX_TAXONOMY) as usize)
+            .await
+            .map_err(graphql_error)?;
+        Ok(tags.into_iter().map(|summary| Tag { summary }).collect())
+    }
+
+    /// Series with published posts, the one with the newest part first.
+    #[graphql(complexity = "first.clamp(1, MAX_TAXONOMY) as usize * child_complexity")]
+    async fn series(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20)] first: i32,
+    ) -> Result<Vec<Series>> {
+        let series = ctx
+            .data::<Arc<BlogService>>()?
+            .series(first.clamp(1, MAX_TAXONOMY) as usize)
+            .await
+            .map_err(graphql_error)?;
+        Ok(series
+            .into_iter()
+            .map(|summary| Series { summary })
+            .collect())
+    }
+}
@@ -0,0 +1,366 @@
+use super::components::{self, Component};
+use super::graphql_error;
+use super::loaders::{AuthorId, Loaders, PostId, PostsOf, RelatedTo, SeriesName};
+use crate::models::blog::{BlogPost, BlogPostSummary, SeriesSummary, TagSummary};
+use crate::models::listing::{self, PostCursor, PostListQuery, PostOwner, PostPage};
+use crate::models::user::User;
+use crate::services::blog::{parse_cursor, BlogService, RELATED_POSTS_LIMIT};
+use crate::services::validation::ValidationErrors;
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Enum, InputObject, Object, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How many parts a series counts as when limiting complexity.
+pub const SERIES_PARTS: usize = 20;
+
+pub type PostConnection = Connection<String, Post>;
+
+#[derive(Enum, Clone, Copy, Default, PartialEq, Eq)]
+#[graphql(remote = "listing::PostSort")]
+pub enum PostSort {
+    /// Most recently published first.
+    #[default]
+    Newest,
+    Oldest,
+}
+
+/// Which published posts to list. Fields left out match every post.
+#[derive(InputObject, Default)]
+#[graphql(name = "PostFilter")]
+pub struct PostFilterInput {
+    pub tag: Option<String>,
+    /// ID of the user who wrote the posts.
+    pub author: Option<Uuid>,
+    /// First day of publication to include, in UTC.
+    pub from: Option<NaiveDate>,
+    /// Last day of publication to include, in UTC.
+    pub to: Option<NaiveDate>,
+    pub has_cover: Option<bool>,
+}
+
+/// A page of the published posts matching `filter`, after the post whose
+/// edge had the cursor `after`, as the post list API lists them.
+pub async fn post_connection(
+    ctx: &Context<'_>,
+    first: i32,
+    after: Option<String>,
+    sort: PostSort,
+    filter: PostFilterInput,
+) -> Result<PostConnection> {
+    let query = PostListQuery {
+        cursor: after,
+        per_page: Some(first.max(0) as usize),
+        sort: sort.into(),
+        tag: filter.tag,
+        author: filter.author,
+        from: filter.from,
+        to: filter.to,
+        has_cover: filter.has_cover,
+    };
+    let page = ctx
+        .data::<Arc<BlogService>>()?
+        .list_posts_page(&query)
+        .await
+        .map_err(|error| graphql_error(argument_paths(error)))?;
+    Ok(connection(page, query.cursor.is_some(), query.sort))
+}
+
+/// A page of the published posts of `owner`, like [`post_connection`]
+/// lists them. It is loaded together with the pages the other authors or
+/// tags of the query ask for.
+async fn owned_post_connection(
+    ctx: &Context<'_>,
+    owner: PostOwner,
+    first: i32,
+    after: Option<String>,
+    sort: PostSort,
+) -> Result<PostConnection> {
+    let sort = sort.into();
+    let after = parse_cursor(after.as_deref(), sort)
+        .map_err(|errors| graphql_error(argument_paths(errors.into())))?;
+    let key = PostsOf {
+        owner,
+        per_page: (first.max(0) as usize).clamp(1, listing::MAX_PER_PAGE),
+        sort,
+        after,
+    };
+    let page = loader(ctx)?.load_one(key).await?.unwrap_or_default();
+    Ok(connection(page, after.is_some(), sort))
+}
+
+fn connection(page: PostPage, has_previous: bool, sort: listing::PostSort) -> PostConnection {
+    let mut connection = Connection::new(has_previous, page.next_cursor.is_some());
+    connection.edges = page
+        .posts
+        .into_iter()
+        .map(|post| {
+            let cursor = PostCursor::after(&post, sort)
+                .map(|cursor| cursor.encode())
+                .unwrap_or_default();
+            Edge::new(cursor, Post::new(post))
+        })
+        .collect();
+    connection
+}
+
+/// Validation errors of the post list name its query string parameters;
+/// point them at the arguments of the connection instead.
+fn argument_paths(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast::<ValidationErrors>() {
+        Ok(mut errors) => {
+            for issue in &mut errors.errors {
+                issue.path = match issue.path.as_str() {
+                    "$.cursor" => "$.after".to_string(),
+                    path => path.replacen("$.", "$.filter.", 1),
+                };
+            }
+            errors.into()
+        }
+        Err(error) => error,
+    }
+}
+
+/// A published post. Lists only fetch summaries; the components and series
+/// of the posts a query asks them of are loaded together.
+pub struct Post {
+    summary: BlogPostSummary,
+    full: Option<BlogPost>,
+}
+
+impl Post {
+    pub fn new(summary: BlogPostSummary) -> Self {
+        Self {
+            summary,
+            full: None,
+        }
+    }
+
+    pub fn full(post: BlogPost) -> Self {
+        Self {
+            summary: post.summary(),
+            full: Some(post),
+        }
+    }
+
+    async fn load(&self, ctx: &Context<'_>) -> Result<Option<BlogPost>> {
+        if let Some(post) = &self.full {
+            return Ok(Some(post.clone()));
+        }
+        loader(ctx)?.load_one(PostId(self.summary.id)).await
+    }
+}
+
+#[Object]
+impl Post {
+    async fn id(&self) -> Uuid {
+        self.summary.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.summary.title
+    }
+
+    async fn slug(&self) -> &str {
+        &self.summary.slug
+    }
+
+    async fn published_at(&self) -> Option<DateTime<Utc>> {
+        self.summary.published_at
+    }
+
+    async fn cover_image(&self) -> Option<&str> {
+        self.summary.cover_image.as_deref()
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.summary.tags
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.summary.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.summary.updated_at
+    }
+
+    /// The user who wrote the post, if credited.
+    async fn author(&self, ctx: &Context<'_>) -> Result<Option<Author>> {
+        let Some(author_id) = self.summary.author_id else {
+            return Ok(None);
+        };
+        let user = loader(ctx)?.load_one(AuthorId(author_id)).await?;
+        Ok(user.map(|user| Author { user }))
+    }
+
+    /// The body of the post. Components this server cannot read are left
+    /// out.
+    async fn components(&self, ctx: &Context<'_>) -> Result<Vec<Component>> {
+        let post = self.load(ctx).await?;
+        Ok(post
+            .map(|post| components::components(&post.components))
+            .unwrap_or_default())
+    }
+
+    /// The series the post is a part of.
+    async fn series(&self, ctx: &Context<'_>) -> Result<Option<PostSeries>> {
+        let Some(name) = self.load(ctx).await?.and_then(|post| post.series) else {
+            return Ok(None);
+        };
+        let posts = loader(ctx)?
+            .load_one(SeriesName(name.clone()))
+            .await?
+            .unwrap_or_default();
+        let index = posts.iter().position(|post| post.id == self.summary.id);
+        Ok(Some(PostSeries { name, posts, index }))
+    }
+
+    /// Published posts on similar topics, most related first.
+    #[graphql(complexity = "RELATED_POSTS_LIMIT * child_complexity")]
+    async fn related(&self, ctx: &Context<'_>) -> Result<Vec<Post>> {
+        let posts = loader(ctx)?
+            .load_one(RelatedTo(self.summary.id))
+            .await?
+            .unwrap_or_default();
+        Ok(posts.into_iter().map(Post::new).collect())
+    }
+}
+
+/// A series as seen from one of its parts.
+pub struct PostSeries {
+    name: String,
+    /// The published parts, in reading order.
+    posts: Vec<BlogPostSummary>,
+    /// Where the part is among them.
+    index: Option<usize>,
+}
+
+#[Object]
+impl PostSeries {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Which part the post is, from 1.
+    async fn position(&self) -> Option<i32> {
+        self.index.map(|index| index as i32 + 1)
+    }
+
+    /// The part before the post.
+    async fn previous(&self) -> Option<Post> {
+        let index = self.index?.checked_sub(1)?;
+        self.posts.get(index).cloned().map(Post::new)
+    }
+
+    /// The part after the post.
+    async fn next(&self) -> Option<Post> {
+        self.posts.get(self.index? + 1).cloned().map(Post::new)
+    }
+
+    /// All published parts, in reading order.
+    #[graphql(complexity = "SERIES_PARTS * child_complexity")]
+    async fn posts(&self) -> Vec<Post> {
+        self.posts.iter().cloned().map(Post::new).collect()
+    }
+}
+
+/// A user credited with posts. Their email stays private.
+pub struct Author {
+    pub user: User,
+}
+
+#[Object]
+impl Author {
+    async fn id(&self) -> Uuid {
+        self.user.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.user.name
+    }
+
+    /// The author's published posts.
+    #[graphql(
+        complexity = "first.clamp(1, listing::MAX_PER_PAGE as i32) as usize * child_complexity"
+    )]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] first: i32,
+        after: Option<String>,
+        #[graphql(default)] sort: PostSort,
+    ) -> Result<PostConnection> {
+        let owner = PostOwner::Author(self.user.id);
+        owned_post_connection(ctx, owner, first, after, sort).await
+    }
+}
+
+/// A tag of published posts.
+pub struct Tag {
+    pub summary: TagSummary,
+}
+
+#[Object]
+impl Tag {
+    async fn name(&self) -> &str {
+        &self.summary.name
+    }
+
+    /// Published posts with the tag.
+    async fn post_count(&self) -> i64 {
+        self.summary.posts
+    }
+
+    #[graphql(
+        complexity = "first.clamp(1, listing::MAX_PER_PAGE as i32) as usize * child_complexity"
+    )]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] first: i32,
+        after: Option<String>,
+        #[graphql(default)] sort: PostSort,
+    ) -> Result<PostConnection> {
+        let owner = PostOwner::Tag(self.summary.name.clone());
+        owned_post_connection(ctx, owner, first, after, sort).await
+    }
+}
+
+/// A series with published posts.
+pub struct Series {
+    pub summary: SeriesSummary,
+}
+
+#[Object]
+impl Series {
+    async fn name(&self) -> &str {
+        &self.summary.name
+    }
+
+    /// Published parts of the series.
+    async fn post_count(&self) -> i64 {
+        self.summary.posts
+    }
+
+    /// When its newest part was published.
+    async fn last_published_at(&self) -> DateTime<Utc> {
+        self.summary.last_published_at
+    }
+
+    /// The published parts, in reading order.
+    #[graphql(complexity = "SERIES_PARTS * child_complexity")]
+    async fn posts(&self, ctx: &Context<'_>) -> Result<Vec<Post>> {
+        let posts = loader(ctx)?
+            .load_one(SeriesName(self.summary.name.clone()))
+            .await?
+            .unwrap_or_default();
+        Ok(posts.into_iter().map(Post::new).collect())
+    }
+}
+
+fn loader<'a>(ctx: &Context<'a>) -> Result<&'a DataLoader<Loaders>> {
+    ctx.data::<DataLoader<Loaders>>()
+}
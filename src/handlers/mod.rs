@@ -1,2 +1,62 @@
+pub mod admin;
 pub mod api;
+pub mod error;
+pub mod graphql;
 pub mod web;
+
+use crate::services::blog::BlogService;
+use crate::services::redirects::RedirectService;
+use actix_web::{http::header, HttpRequest, HttpResponse};
+
+/// A `301` (or `302` when not `permanent`) to `location`, keeping the query
+/// string of the request.
+pub fn redirect(req: &HttpRequest, location: &str, permanent: bool) -> HttpResponse {
+    let location = match req.query_string() {
+        "" => location.to_string(),
+        query if !location.contains('?') => format!("{}?{}", location, query),
+        _ => location.to_string(),
+    };
+
+    let mut response = if permanent {
+        HttpResponse::MovedPermanently()
+    } else {
+        HttpResponse::Found()
+    };
+    response
+        .insert_header((header::LOCATION, location))
+        .finish()
+}
+
+/// Where a request for the missing post `slug` should go instead: the
+/// post's current URL (built by `location`) when it was renamed, or an
+/// admin-managed redirect for the request path. Lookup failures are logged
+/// and treated as no redirect, so the caller can still answer 404.
+pub async fn moved_post(
+    req: &HttpRequest,
+    blog_service: &BlogService,
+    redirect_service: &RedirectService,
+    slug: &str,
+    location: impl Fn(&str) -> String,
+) -> Option<HttpResponse> {
+    match blog_service.current_slug(slug).await {
+        Ok(Some(current)) => return Some(redirect(req, &location(&current), true)),
+        Ok(None) => {}
+        Err(error) => tracing::warn!(slug = %slug, "Failed to look up slug history: {:?}", error),
+    }
+    moved_path(req, redirect_service).await
+}
+
+/// The admin-managed redirect for the request path, if any.
+pub async fn moved_path(
+    req: &HttpRequest,
+    redirect_service: &RedirectService,
+) -> Option<HttpResponse> {
+    match redirect_service.find(req.path()).await {
+        Ok(Some(found)) => Some(redirect(req, &found.to_path, found.permanent)),
+        Ok(None) => None,
+        Err(error) => {
+            tracing::warn!(path = %req.path(), "Failed to look up redirect: {:?}", error);
+            None
+        }
+    }
+}
@@ -1,56 +1,190 @@
-use crate::services::blog::BlogService;
+use crate::handlers::error::AppError;
+use crate::handlers::{moved_path, moved_post};
+use crate::models::blog::{ArchivePeriod, ArchiveYear};
+use crate::services::blog::{BlogService, SEARCH_RESULTS_LIMIT};
+use crate::services::redirects::RedirectService;
 use crate::templates::TemplateEngine;
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
 use std::sync::Arc;
 
-pub async fn home(template_engine: web::Data<Arc<TemplateEngine>>) -> ActixResult<HttpResponse> {
-    match template_engine.render_home() {
-        Ok(html) => Ok(HttpResponse::Ok().content_type("text/html").body(html)),
-        Err(_) => Ok(HttpResponse::InternalServerError().body("Template error")),
-    }
+pub async fn home(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+) -> Result<HttpResponse, AppError> {
+    let homepage = blog_service.homepage().await?;
+    let html = template_engine.render_home(&homepage)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
 }
 
 pub async fn blog_list(
     template_engine: web::Data<Arc<TemplateEngine>>,
     blog_service: web::Data<Arc<BlogService>>,
     query: web::Query<std::collections::HashMap<String, String>>,
-) -> ActixResult<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let page = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
 
     let per_page = 10;
 
-    match blog_service.list_posts(page, per_page).await {
-        Ok((posts, total)) => {
-            let total_pages = ((total as f64) / (per_page as f64)).ceil() as usize;
-            match template_engine.render_blog_list(&posts, page, total_pages) {
-                Ok(html) => Ok(HttpResponse::Ok().content_type("text/html").body(html)),
-                Err(error) => Ok(HttpResponse::InternalServerError().body(error.to_string())),
-            }
+    let (posts, total) = blog_service.list_posts(page, per_page).await?;
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as usize;
+    if let Some(redirect) = page_out_of_range("/blog", page, total_pages) {
+        return Ok(redirect);
+    }
+    let archive = archive_sidebar(&blog_service).await;
+    let html = template_engine.render_blog_list(&posts, page, total_pages, &archive)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+pub async fn archive_year(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+    path: web::Path<i32>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> Result<HttpResponse, AppError> {
+    let period = ArchivePeriod {
+        year: path.into_inner(),
+        month: None,
+    };
+    archive(&template_engine, &blog_service, period, &query).await
+}
+
+pub async fn archive_month(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+    path: web::Path<(i32, u32)>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> Result<HttpResponse, AppError> {
+    let (year, month) = path.into_inner();
+    let period = ArchivePeriod {
+        year,
+        month: Some(month),
+    };
+    archive(&template_engine, &blog_service, period, &query).await
+}
+
+/// The posts of a year or month. Periods without posts are not pages.
+async fn archive(
+    template_engine: &TemplateEngine,
+    blog_service: &BlogService,
+    period: ArchivePeriod,
+    query: &std::collections::HashMap<String, String>,
+) -> Result<HttpResponse, AppError> {
+    let page = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+
+    let per_page = 10;
+
+    let (posts, total) = match blog_service.list_posts_in(period, page, per_page).await? {
+        Some((_, 0)) | None => {
+            return Err(AppError::not_found(format!(
+                "No posts from {}",
+                period.label()
+            )))
         }
-        Err(_) => Ok(HttpResponse::InternalServerError().body("Database error")),
+        Some(found) => found,
+    };
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as usize;
+    if let Some(redirect) = page_out_of_range(&period.path(), page, total_pages) {
+        return Ok(redirect);
     }
+    let archive = archive_sidebar(blog_service).await;
+    let html = template_engine.render_archive(&period, &posts, page, total_pages, &archive)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+/// Readers asking for a page past the last one, e.g. from an old link after
+/// posts were unpublished, are sent to the last page; page 0 goes to the
+/// first. The list at `path` always has page 1, even when empty.
+fn page_out_of_range(path: &str, page: usize, total_pages: usize) -> Option<HttpResponse> {
+    let last = total_pages.max(1);
+    let target = match page {
+        0 => 1,
+        page if page > last => last,
+        _ => return None,
+    };
+    let location = if target == 1 {
+        path.to_string()
+    } else {
+        format!("{}?page={}", path, target)
+    };
+    Some(
+        HttpResponse::Found()
+            .insert_header((header::LOCATION, location))
+            .finish(),
+    )
+}
+
+/// Months with posts for the sidebar. A nice-to-have; never fail the page
+/// over it.
+async fn archive_sidebar(blog_service: &BlogService) -> Vec<ArchiveYear> {
+    blog_service.archive().await.unwrap_or_else(|error| {
+        tracing::warn!("Failed to load the archive: {:?}", error);
+        Vec::new()
+    })
 }
 
 pub async fn blog_post(
+    req: HttpRequest,
     path: web::Path<String>,
     template_engine: web::Data<Arc<TemplateEngine>>,
     blog_service: web::Data<Arc<BlogService>>,
-) -> ActixResult<HttpResponse> {
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
     let slug = path.into_inner();
 
-    match blog_service.get_post_by_slug(&slug).await {
-        Ok(Some(post)) => match template_engine.render_blog_post(&post) {
-            Ok(html) => Ok(HttpResponse::Ok().content_type("text/html").body(html)),
-            Err(_) => Ok(HttpResponse::InternalServerError().body("Template error")),
-        },
-        Ok(None) => Ok(HttpResponse::NotFound().body("Post not found")),
-        Err(_) => Ok(HttpResponse::InternalServerError().body("Database error")),
-    }
+    let Some(post) = blog_service.get_post_by_slug(&slug).await? else {
+        let moved = moved_post(&req, &blog_service, &redirect_service, &slug, |slug| {
+            format!("/blog/{}", slug)
+        })
+        .await;
+        return moved.ok_or_else(|| AppError::not_found("Post not found"));
+    };
+
+    // Related posts are a nice-to-have; never fail the page over them.
+    let related = blog_service
+        .related_posts(&post)
+        .await
+        .unwrap_or_else(|error| {
+            tracing::warn!(slug = %post.slug, "Failed to load related posts: {:?}", error);
+            Vec::new()
+        });
+
+    let html = template_engine.render_blog_post(&post, &related, false)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
 }
 
-pub async fn contact(template_engine: web::Data<Arc<TemplateEngine>>) -> ActixResult<HttpResponse> {
-    match template_engine.render_contact() {
-        Ok(html) => Ok(HttpResponse::Ok().content_type("text/html").body(html)),
-        Err(_) => Ok(HttpResponse::InternalServerError().body("Template error")),
-    }
+pub async fn contact(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+) -> Result<HttpResponse, AppError> {
+    let html = template_engine.render_contact()?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+pub async fn search(
+    template_engine: web::Data<Arc<TemplateEngine>>,
+    blog_service: web::Data<Arc<BlogService>>,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, AppError> {
+    let posts = match query.q.trim() {
+        "" => Vec::new(),
+        q => blog_service.search_posts(q, SEARCH_RESULTS_LIMIT).await?,
+    };
+    let html = template_engine.render_search(query.q.trim(), &posts)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(html))
+}
+
+/// Anything no route matched: an admin-managed redirect, or 404.
+pub async fn not_found(
+    req: HttpRequest,
+    redirect_service: web::Data<Arc<RedirectService>>,
+) -> Result<HttpResponse, AppError> {
+    moved_path(&req, &redirect_service)
+        .await
+        .ok_or_else(|| AppError::not_found("Page not found"))
 }
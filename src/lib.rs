@@ -0,0 +1,10 @@
+//! Shared by the `ruststack` web server and the `ruststack-admin` command
+//! line tool.
+
+pub mod config;
+pub mod database;
+pub mod handlers;
+pub mod models;
+pub mod services;
+pub mod telemetry;
+pub mod templates;
@@ -1,22 +1,18 @@
 use actix_files::Files;
+use actix_web::middleware::from_fn;
 use actix_web::{web, App, HttpServer};
+use ruststack::config::Config;
+use ruststack::database::Database;
+use ruststack::handlers;
+use ruststack::handlers::graphql::GraphqlApi;
+use ruststack::services::blog::BlogService;
+use ruststack::services::content_sync::{self, ContentSync};
+use ruststack::services::redirects::RedirectService;
+use ruststack::telemetry::Telemetry;
+use ruststack::templates::TemplateEngine;
 use std::sync::Arc;
 use tracing_actix_web::TracingLogger;
 
-mod config;
-mod database;
-mod handlers;
-mod models;
-mod services;
-mod telemetry;
-mod templates;
-
-use config::Config;
-use database::Database;
-use services::blog::BlogService;
-use telemetry::Telemetry;
-use templates::TemplateEngine;
-
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     Telemetry::init_subscriber("Rust Backend", "info".into(), std::io::stdout);
@@ -24,32 +20,120 @@ async fn main() -> std::io::Result<()> {
     dotenvy::dotenv().ok();
 
     let config = Config::from_env().expect("Failed to load configuration");
-    let database = Database::new(&config.database_url)
+    let database = Database::connect(&config.database_url)
         .await
         .expect("Failed to connect to database");
+    if config.auto_migrate {
+        database.migrate().await.expect("Failed to run migrations");
+    }
+    database
+        .check_schema()
+        .await
+        .expect("Database schema check failed");
 
-    let template_engine = Arc::new(TemplateEngine::new().expect("Failed to initialize templates"));
+    let template_engine =
+        Arc::new(TemplateEngine::new(&config).expect("Failed to initialize templates"));
     let blog_service = Arc::new(BlogService::new(database.clone()));
+    let redirect_service = Arc::new(RedirectService::new(database.clone()));
+    let graphql = Arc::new(GraphqlApi::new(blog_service.clone(), database.clone()));
+    let content_sync = config
+        .content_dir
+        .clone()
+        .map(|dir| Arc::new(ContentSync::new(blog_service.clone(), dir)));
+
+    if let Some(content_sync) = content_sync {
+        match content_sync.sync(false).await {
+            Ok(report) => content_sync::log_report(&report),
+            Err(error) => tracing::error!("Failed to sync content directory: {:?}", error),
+        }
+        content_sync
+            .watch()
+            .expect("Failed to watch content directory");
+    }
 
-    println!("Starting server at http://{}:{}", config.host, config.port);
+    let address = format!("{}:{}", config.host, config.port);
+    println!("Starting server at http://{}", address);
 
     HttpServer::new(move || {
         App::new()
+            .wrap(from_fn(handlers::error::render_errors))
             .wrap(TracingLogger::default())
             .app_data(web::Data::new(template_engine.clone()))
             .app_data(web::Data::new(blog_service.clone()))
+            .app_data(web::Data::new(redirect_service.clone()))
+            .app_data(web::Data::new(graphql.clone()))
+            .app_data(web::Data::new(config.clone()))
             .service(
-                web::scope("/api")
-                    .route("/blog/{slug}", web::get().to(handlers::api::get_blog_post))
-                    .route("/blog", web::get().to(handlers::api::list_blog_posts)),
+                web::scope("/api/admin")
+                    .route("/posts", web::post().to(handlers::admin::create_post))
+                    .route("/posts/{id}", web::put().to(handlers::admin::update_post))
+                    .route(
+                        "/posts/{id}/featured",
+                        web::put().to(handlers::admin::set_featured),
+                    )
+                    .route(
+                        "/posts/{id}/author",
+                        web::put().to(handlers::admin::set_author),
+                    )
+                    .route("/featured", web::get().to(handlers::admin::list_featured))
+                    .route(
+                        "/posts/{id}/preview",
+                        web::get().to(handlers::admin::preview_post),
+                    )
+                    .route(
+                        "/posts/{id}/export",
+                        web::get().to(handlers::admin::export_post),
+                    )
+                    .service(
+                        web::resource("/archive")
+                            .app_data(web::PayloadConfig::new(
+                                handlers::admin::ARCHIVE_UPLOAD_LIMIT,
+                            ))
+                            .route(web::get().to(handlers::admin::export_archive))
+                            .route(web::post().to(handlers::admin::import_archive)),
+                    )
+                    .route(
+                        "/import/markdown",
+                        web::post().to(handlers::admin::import_markdown),
+                    )
+                    .route(
+                        "/tables/csv",
+                        web::post().to(handlers::admin::import_csv_table),
+                    )
+                    .route("/redirects", web::get().to(handlers::admin::list_redirects))
+                    .route(
+                        "/redirects",
+                        web::post().to(handlers::admin::create_redirect),
+                    )
+                    .route(
+                        "/redirects/{id}",
+                        web::delete().to(handlers::admin::delete_redirect),
+                    ),
             )
+            .service(web::scope(handlers::api::v1::PREFIX).configure(handlers::api::v1::routes))
+            .service(
+                web::scope(handlers::api::LEGACY_PREFIX)
+                    .wrap(from_fn(handlers::api::deprecated))
+                    .configure(handlers::api::v1::routes),
+            )
+            .route("/graphql", web::post().to(handlers::graphql::graphql))
             .route("/", web::get().to(handlers::web::home))
             .route("/blog", web::get().to(handlers::web::blog_list))
+            .route(
+                r"/blog/{year:\d{4}}",
+                web::get().to(handlers::web::archive_year),
+            )
+            .route(
+                r"/blog/{year:\d{4}}/{month:\d{1,2}}",
+                web::get().to(handlers::web::archive_month),
+            )
             .route("/blog/{slug}", web::get().to(handlers::web::blog_post))
             .route("/contact", web::get().to(handlers::web::contact))
+            .route("/search", web::get().to(handlers::web::search))
             .service(Files::new("/static", "./static").show_files_listing())
+            .default_service(web::to(handlers::web::not_found))
     })
-    .bind(format!("{}:{}", config.host, config.port))?
+    .bind(address)?
     .run()
     .await
 }
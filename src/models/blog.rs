@@ -1,31 +1,200 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct BlogPost {
     pub id: Uuid,
     pub title: String,
     pub slug: String,
     pub published_at: Option<DateTime<Utc>>,
     pub cover_image: Option<String>,
+    pub tags: Vec<String>,
+    pub series: Option<String>,
+    pub series_position: Option<i32>,
+    #[serde(with = "component_list")]
+    #[schemars(with = "Vec<BlogComponent>")]
     pub components: Vec<BlogComponent>,
+    /// The user who wrote the post. Archives restored on another site keep
+    /// it only where that user exists.
+    #[serde(default)]
+    pub author_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl BlogPost {
+    /// The post without its components, as posts are listed.
+    pub fn summary(&self) -> BlogPostSummary {
+        BlogPostSummary {
+            id: self.id,
+            title: self.title.clone(),
+            slug: self.slug.clone(),
+            published_at: self.published_at,
+            cover_image: self.cover_image.clone(),
+            tags: self.tags.clone(),
+            author_id: self.author_id,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+
+    /// The post as the admin API accepts it, for validating stored posts.
+    pub fn to_input(&self) -> BlogPostInput {
+        BlogPostInput {
+            title: self.title.clone(),
+            slug: self.slug.clone(),
+            published_at: self.published_at,
+            cover_image: self.cover_image.clone(),
+            tags: self.tags.clone(),
+            series: self.series.clone(),
+            series_position: self.series_position,
+            components: self.components.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct BlogPostSummary {
     pub id: Uuid,
     pub title: String,
     pub slug: String,
     pub published_at: Option<DateTime<Utc>>,
     pub cover_image: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub author_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A post featured on the homepage.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeaturedPost {
+    #[serde(flatten)]
+    pub post: BlogPostSummary,
+    /// Featured posts are shown by ascending position, then newest first.
+    pub featured_position: Option<i32>,
+}
+
+/// Payload accepted by the admin API when featuring a post.
+#[derive(Debug, Deserialize)]
+pub struct FeaturedInput {
+    pub featured: bool,
+    #[serde(default)]
+    pub position: Option<i32>,
+}
+
+/// A post's place on the homepage, as kept in site archives.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeaturedEntry {
+    pub post_id: Uuid,
+    pub position: Option<i32>,
+}
+
+/// A slug a post used to have; requests for it redirect to the post.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OldSlug {
+    pub slug: String,
+    pub post_id: Uuid,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Payload accepted by the admin API when crediting a post; `null` leaves
+/// the post without an author.
+#[derive(Debug, Deserialize)]
+pub struct AuthorInput {
+    pub author_id: Option<Uuid>,
+}
+
+/// A series with published posts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesSummary {
+    pub name: String,
+    pub posts: i64,
+    /// Slug of the series' first part, where readers should start.
+    pub first_slug: String,
+    /// When its newest part was published.
+    pub last_published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagSummary {
+    pub name: String,
+    /// Published posts with the tag.
+    pub posts: i64,
+}
+
+/// A year of published posts, for the archive.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ArchiveYear {
+    pub year: i32,
+    pub posts: i64,
+    /// Months with posts, newest first.
+    pub months: Vec<ArchiveMonth>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ArchiveMonth {
+    /// 1 to 12.
+    pub month: u32,
+    pub posts: i64,
+}
+
+/// A year, or a month of it, in UTC.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchivePeriod {
+    pub year: i32,
+    pub month: Option<u32>,
+}
+
+impl ArchivePeriod {
+    /// The start of the period and the start of the next one, or `None` for
+    /// a month that doesn't exist.
+    pub fn range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = Utc
+            .with_ymd_and_hms(self.year, self.month.unwrap_or(1), 1, 0, 0, 0)
+            .single()?;
+        let end = match self.month {
+            Some(12) | None => Utc.with_ymd_and_hms(self.year + 1, 1, 1, 0, 0, 0),
+            Some(month) => Utc.with_ymd_and_hms(self.year, month + 1, 1, 0, 0, 0),
+        }
+        .single()?;
+        Some((start, end))
+    }
+
+    /// "March 2025", or "2025".
+    pub fn label(&self) -> String {
+        match self.range() {
+            Some((start, _)) if self.month.is_some() => start.format("%B %Y").to_string(),
+            _ => self.year.to_string(),
+        }
+    }
+
+    /// `/blog/2025/03`, or `/blog/2025`.
+    pub fn path(&self) -> String {
+        match self.month {
+            Some(month) => format!("/blog/{}/{:02}", self.year, month),
+            None => format!("/blog/{}", self.year),
+        }
+    }
+}
+
+/// Everything the homepage shows.
+#[derive(Debug, Serialize, Clone)]
+pub struct Homepage {
+    pub featured: Vec<BlogPostSummary>,
+    /// Newest posts, leaving out the featured ones.
+    pub latest: Vec<BlogPostSummary>,
+    /// Series that most recently got a new part.
+    pub series: Vec<SeriesSummary>,
+    /// Tags on the most posts.
+    pub tags: Vec<TagSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type")]
 pub enum BlogComponent {
     #[serde(rename = "heading")]
@@ -38,7 +207,11 @@ pub enum BlogComponent {
     Code { language: String, code: String },
 
     #[serde(rename = "callout")]
-    Callout { style: String, markdown: String },
+    Callout {
+        #[schemars(extend("enum" = ["info", "warning", "success", "error", "note"]))]
+        style: String,
+        markdown: String,
+    },
 
     #[serde(rename = "card")]
     Card {
@@ -59,4 +232,557 @@ pub enum BlogComponent {
         text: String,
         author: Option<String>,
     },
+
+    #[serde(rename = "tabs")]
+    Tabs { tabs: Vec<Tab> },
+
+    #[serde(rename = "details")]
+    Details {
+        summary: String,
+        #[serde(default)]
+        markdown: Option<String>,
+        #[serde(default, with = "component_list")]
+        #[schemars(with = "Vec<BlogComponent>")]
+        components: Vec<BlogComponent>,
+        #[serde(default)]
+        open: bool,
+    },
+
+    #[serde(rename = "steps")]
+    Steps { steps: Vec<Step> },
+
+    #[serde(rename = "math")]
+    Math { latex: String },
+
+    /// A diagram in the Graphviz DOT language.
+    #[serde(rename = "diagram")]
+    Diagram {
+        source: String,
+        caption: Option<String>,
+    },
+
+    /// A self-hosted video file.
+    #[serde(rename = "video")]
+    Video {
+        src: String,
+        /// A self-hosted image, like `src`.
+        poster: Option<String>,
+        #[serde(default)]
+        captions: Vec<CaptionTrack>,
+    },
+
+    /// Third-party content, rendered as a click-to-load facade.
+    #[serde(rename = "embed")]
+    Embed {
+        provider: EmbedProvider,
+        url: String,
+        title: Option<String>,
+    },
+
+    #[serde(rename = "table")]
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+        /// Per-column alignment; missing entries default to left.
+        #[serde(default)]
+        alignment: Vec<ColumnAlignment>,
+        caption: Option<String>,
+        #[serde(default)]
+        sortable: bool,
+    },
+
+    /// A component that failed to deserialize, e.g. an unknown `type` or a
+    /// missing field. It is never accepted on write; lists of components
+    /// serialize it back as its original JSON (see [`component_list`]) so
+    /// nothing is lost.
+    #[serde(skip)]
+    #[schemars(skip)]
+    Unknown(UnknownComponent),
+}
+
+#[derive(Debug, Clone)]
+pub struct UnknownComponent {
+    pub raw: Value,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl ColumnAlignment {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "left" => Some(ColumnAlignment::Left),
+            "center" => Some(ColumnAlignment::Center),
+            "right" => Some(ColumnAlignment::Right),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CaptionTrack {
+    pub src: String,
+    pub srclang: String,
+    pub label: String,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbedProvider {
+    YouTube,
+    Vimeo,
+    Gist,
+    CodePen,
+}
+
+impl EmbedProvider {
+    pub const ALL: [EmbedProvider; 4] = [
+        EmbedProvider::YouTube,
+        EmbedProvider::Vimeo,
+        EmbedProvider::Gist,
+        EmbedProvider::CodePen,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|provider| provider.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Identifier used in component JSON and in `EMBED_PROVIDERS`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmbedProvider::YouTube => "youtube",
+            EmbedProvider::Vimeo => "vimeo",
+            EmbedProvider::Gist => "gist",
+            EmbedProvider::CodePen => "codepen",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            EmbedProvider::YouTube => "YouTube",
+            EmbedProvider::Vimeo => "Vimeo",
+            EmbedProvider::Gist => "GitHub Gist",
+            EmbedProvider::CodePen => "CodePen",
+        }
+    }
+
+    /// Host serving the iframe once a reader opts in.
+    pub fn embed_host(&self) -> &'static str {
+        match self {
+            EmbedProvider::YouTube => "www.youtube-nocookie.com",
+            EmbedProvider::Vimeo => "player.vimeo.com",
+            EmbedProvider::Gist => "gist.github.com",
+            EmbedProvider::CodePen => "codepen.io",
+        }
+    }
+
+    /// Translate a public page URL (e.g. a YouTube watch link) into the
+    /// URL that can be loaded in an iframe. Returns `None` when the URL
+    /// doesn't belong to this provider.
+    pub fn embed_url(&self, url: &str) -> Option<String> {
+        let url = url::Url::parse(url).ok()?;
+        if url.scheme() != "https" {
+            return None;
+        }
+        let host = url.host_str()?.trim_start_matches("www.");
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let is_id = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+
+        match (self, host, segments.as_slice()) {
+            (EmbedProvider::YouTube, "youtube.com", ["watch"]) => {
+                let id = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "v")
+                    .map(|(_, value)| value.into_owned())?;
+                is_id(&id).then(|| format!("https://{}/embed/{}", self.embed_host(), id))
+            }
+            (EmbedProvider::YouTube, "youtube.com", ["embed", id])
+            | (EmbedProvider::YouTube, "youtu.be", [id]) => {
+                is_id(id).then(|| format!("https://{}/embed/{}", self.embed_host(), id))
+            }
+            (EmbedProvider::Vimeo, "vimeo.com", [id]) => (is_id(id)
+                && id.chars().all(|c| c.is_ascii_digit()))
+            .then(|| format!("https://{}/video/{}?dnt=1", self.embed_host(), id)),
+            (EmbedProvider::Gist, "gist.github.com", [user, id]) => (is_id(user) && is_id(id))
+                .then(|| format!("https://{}/{}/{}.pibb", self.embed_host(), user, id)),
+            (EmbedProvider::CodePen, "codepen.io", [user, "pen", id]) => (is_id(user) && is_id(id))
+                .then(|| {
+                    format!(
+                        "https://{}/{}/embed/{}?default-tab=result",
+                        self.embed_host(),
+                        user,
+                        id
+                    )
+                }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Tab {
+    pub label: String,
+    #[serde(with = "component_list")]
+    #[schemars(with = "Vec<BlogComponent>")]
+    pub components: Vec<BlogComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Step {
+    pub title: String,
+    #[serde(default)]
+    pub markdown: Option<String>,
+    #[serde(default, with = "component_list")]
+    #[schemars(with = "Vec<BlogComponent>")]
+    pub components: Vec<BlogComponent>,
+}
+
+impl BlogComponent {
+    /// Plain text carried by the component, used for search and similarity.
+    pub fn text(&self) -> String {
+        match self {
+            BlogComponent::Heading { text } => text.clone(),
+            BlogComponent::Paragraph { markdown } => markdown.clone(),
+            BlogComponent::Code { code, .. } => code.clone(),
+            BlogComponent::Callout { markdown, .. } => markdown.clone(),
+            BlogComponent::Card {
+                title, description, ..
+            } => format!("{} {}", title, description),
+            BlogComponent::Image { alt, caption, .. } => match caption {
+                Some(caption) => format!("{} {}", alt, caption),
+                None => alt.clone(),
+            },
+            BlogComponent::Quote { text, author } => match author {
+                Some(author) => format!("{} {}", text, author),
+                None => text.clone(),
+            },
+            BlogComponent::Tabs { tabs } => tabs
+                .iter()
+                .map(|tab| format!("{} {}", tab.label, components_text(&tab.components)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            BlogComponent::Details {
+                summary,
+                markdown,
+                components,
+                ..
+            } => format!(
+                "{} {} {}",
+                summary,
+                markdown.as_deref().unwrap_or_default(),
+                components_text(components)
+            ),
+            BlogComponent::Steps { steps } => steps
+                .iter()
+                .map(|step| {
+                    format!(
+                        "{} {} {}",
+                        step.title,
+                        step.markdown.as_deref().unwrap_or_default(),
+                        components_text(&step.components)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            BlogComponent::Math { .. } => String::new(),
+            BlogComponent::Diagram { caption, .. } => caption.clone().unwrap_or_default(),
+            BlogComponent::Video { .. } => String::new(),
+            BlogComponent::Embed { title, .. } => title.clone().unwrap_or_default(),
+            BlogComponent::Table {
+                headers,
+                rows,
+                caption,
+                ..
+            } => {
+                let mut parts = vec![caption.clone().unwrap_or_default(), headers.join(" ")];
+                parts.extend(rows.iter().map(|row| row.join(" ")));
+                parts.join(" ")
+            }
+            BlogComponent::Unknown(_) => String::new(),
+        }
+    }
+
+    /// Deserialize a component, falling back to [`BlogComponent::Unknown`]
+    /// instead of failing.
+    pub fn from_value_lenient(value: Value) -> Self {
+        match serde_json::from_value(value.clone()) {
+            Ok(component) => component,
+            Err(error) => BlogComponent::Unknown(UnknownComponent {
+                raw: value,
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    /// Components nested directly inside this one.
+    pub fn children(&self) -> Vec<&BlogComponent> {
+        match self {
+            BlogComponent::Tabs { tabs } => tabs.iter().flat_map(|tab| &tab.components).collect(),
+            BlogComponent::Details { components, .. } => components.iter().collect(),
+            BlogComponent::Steps { steps } => {
+                steps.iter().flat_map(|step| &step.components).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Serde helpers for `Vec<BlogComponent>` fields, used with
+/// `#[serde(with = "component_list")]`.
+///
+/// Entries are deserialized leniently, so one malformed component becomes
+/// [`BlogComponent::Unknown`] rather than failing the whole post, and unknown
+/// entries serialize back to the JSON they were read from.
+pub mod component_list {
+    use super::BlogComponent;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    struct Entry<'a>(&'a BlogComponent);
+
+    impl Serialize for Entry<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.0 {
+                BlogComponent::Unknown(unknown) => unknown.raw.serialize(serializer),
+                component => component.serialize(serializer),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        components: &[BlogComponent],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(components.iter().map(Entry))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<BlogComponent>, D::Error> {
+        let values = Vec::<Value>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(BlogComponent::from_value_lenient)
+            .collect())
+    }
+}
+
+/// Parse the `components` JSONB column of a post.
+pub fn components_from_json(value: Value) -> serde_json::Result<Vec<BlogComponent>> {
+    component_list::deserialize(value)
+}
+
+/// Every unknown component in `components`, including nested ones, paired
+/// with the index of the top-level component that contains it.
+pub fn unknown_components(components: &[BlogComponent]) -> Vec<(usize, &UnknownComponent)> {
+    fn collect<'a>(
+        index: usize,
+        component: &'a BlogComponent,
+        found: &mut Vec<(usize, &'a UnknownComponent)>,
+    ) {
+        if let BlogComponent::Unknown(unknown) = component {
+            found.push((index, unknown));
+        }
+        for child in component.children() {
+            collect(index, child, found);
+        }
+    }
+
+    let mut found = Vec::new();
+    for (index, component) in components.iter().enumerate() {
+        collect(index, component, &mut found);
+    }
+    found
+}
+
+/// Plain text of a list of components, joined with spaces.
+pub fn components_text(components: &[BlogComponent]) -> String {
+    components
+        .iter()
+        .map(BlogComponent::text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The slice of a published post needed to rank related posts.
+#[derive(Debug, Clone)]
+pub struct RelatedCandidate {
+    pub id: Uuid,
+    pub tags: Vec<String>,
+    pub series: Option<String>,
+    pub components: Vec<BlogComponent>,
+}
+
+/// Where a post synced from the content directory came from, and the state
+/// of both sides when it was last synced.
+#[derive(Debug, Clone)]
+pub struct PostSource {
+    pub id: Uuid,
+    pub slug: String,
+    /// File path relative to the content directory; `None` for posts created
+    /// through the admin API.
+    pub source_path: Option<String>,
+    pub source_hash: Option<String>,
+    /// When anything a content file defines last changed, whether a post is
+    /// published aside.
+    pub content_updated_at: DateTime<Utc>,
+    /// When the post was last saved from its file; content changed after
+    /// that is a database edit.
+    pub source_synced_at: Option<DateTime<Utc>>,
+}
+
+/// The content file a post is saved from, relative to the content
+/// directory, and the hash of its contents.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceFile<'a> {
+    pub path: &'a str,
+    pub hash: &'a str,
+}
+
+/// Payload accepted by the admin API when creating or updating a post.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlogPostInput {
+    pub title: String,
+    /// Generated from the title when a post is created without one; an
+    /// update without one keeps the current slug.
+    #[serde(default)]
+    pub slug: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub series: Option<String>,
+    pub series_position: Option<i32>,
+    #[serde(default, with = "component_list")]
+    pub components: Vec<BlogComponent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn unknown_component_post() -> Value {
+        json!({
+            "title": "A post",
+            "components": [
+                { "type": "heading", "text": "Fine" },
+                { "type": "hologram", "frames": [1, 2, 3] },
+                { "type": "tabs", "tabs": [
+                    { "label": "Tab", "components": [{ "type": "heading", "level": 2 }] },
+                ] },
+            ],
+        })
+    }
+
+    #[test]
+    fn unreadable_components_are_kept_as_unknown() {
+        let post: BlogPostInput = serde_json::from_value(unknown_component_post()).unwrap();
+        let unknown = unknown_components(&post.components);
+        assert_eq!(unknown.len(), 2);
+
+        let (index, hologram) = unknown[0];
+        assert_eq!(index, 1);
+        assert_eq!(
+            hologram.raw,
+            json!({ "type": "hologram", "frames": [1, 2, 3] })
+        );
+        assert!(hologram.error.contains("hologram"), "{}", hologram.error);
+
+        let (index, heading) = unknown[1];
+        assert_eq!(index, 2);
+        assert_eq!(heading.raw, json!({ "type": "heading", "level": 2 }));
+        assert!(heading.error.contains("text"), "{}", heading.error);
+    }
+
+    #[test]
+    fn unknown_components_serialize_back_unchanged() {
+        let original = unknown_component_post();
+        let post: BlogPostInput = serde_json::from_value(original.clone()).unwrap();
+        let json = serde_json::to_value(&post).unwrap();
+        assert_eq!(json["components"], original["components"]);
+    }
+
+    #[test]
+    fn embed_urls_of_each_provider() {
+        let cases = [
+            (
+                EmbedProvider::YouTube,
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=10",
+                "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+            ),
+            (
+                EmbedProvider::YouTube,
+                "https://youtu.be/dQw4w9WgXcQ",
+                "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+            ),
+            (
+                EmbedProvider::Vimeo,
+                "https://vimeo.com/76979871",
+                "https://player.vimeo.com/video/76979871?dnt=1",
+            ),
+            (
+                EmbedProvider::Gist,
+                "https://gist.github.com/octocat/6cad326836d38bd3a7ae",
+                "https://gist.github.com/octocat/6cad326836d38bd3a7ae.pibb",
+            ),
+            (
+                EmbedProvider::CodePen,
+                "https://codepen.io/team/pen/abcDEF",
+                "https://codepen.io/team/embed/abcDEF?default-tab=result",
+            ),
+        ];
+        for (provider, url, embed_url) in cases {
+            assert_eq!(
+                provider.embed_url(url).as_deref(),
+                Some(embed_url),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn embed_urls_reject_other_pages() {
+        let cases = [
+            (
+                EmbedProvider::YouTube,
+                "http://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            ),
+            (
+                EmbedProvider::YouTube,
+                "https://www.youtube.com/watch?v=a\"b",
+            ),
+            (
+                EmbedProvider::YouTube,
+                "https://evil.example/watch?v=dQw4w9WgXcQ",
+            ),
+            (EmbedProvider::YouTube, "https://vimeo.com/76979871"),
+            (EmbedProvider::Vimeo, "https://vimeo.com/channels"),
+            (EmbedProvider::Gist, "https://gist.github.com/octocat"),
+            (
+                EmbedProvider::CodePen,
+                "https://codepen.io/team/full/abcDEF",
+            ),
+            (EmbedProvider::CodePen, "not a url"),
+        ];
+        for (provider, url) in cases {
+            assert_eq!(provider.embed_url(url), None, "{}", url);
+        }
+    }
 }
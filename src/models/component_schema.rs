@@ -0,0 +1,195 @@
+//! Versioning of the stored component JSON.
+//!
+//! Every post records the `schema_version` its `components` were written
+//! with. When the shape of [`BlogComponent`](super::blog::BlogComponent)
+//! changes, bump [`CURRENT_SCHEMA_VERSION`] and append a function to
+//! [`UPGRADES`] that rewrites version N JSON into version N+1. Old rows are
+//! upgraded in memory whenever they are read, and can be rewritten in bulk
+//! with `ruststack-admin upgrade-components`.
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Version of the component JSON written by this build.
+pub const CURRENT_SCHEMA_VERSION: i32 = 2;
+
+type Upgrade = fn(&mut Value);
+
+/// `UPGRADES[n]` upgrades components from version `n + 1` to `n + 2`.
+const UPGRADES: &[Upgrade] = &[v1_to_v2];
+
+// Keep the registry in step with the current version.
+const _: () = assert!(UPGRADES.len() as i32 == CURRENT_SCHEMA_VERSION - 1);
+
+#[derive(Debug)]
+pub struct UnsupportedSchemaVersion(pub i32);
+
+impl fmt::Display for UnsupportedSchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "component schema version {} is not supported (this build reads 1 to {})",
+            self.0, CURRENT_SCHEMA_VERSION
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedSchemaVersion {}
+
+/// Upgrade the `components` JSON of a post from `version` to
+/// [`CURRENT_SCHEMA_VERSION`].
+///
+/// Versions newer than this build knows about are rejected rather than
+/// guessed at, since they were written by a newer deployment.
+pub fn upgrade_components(
+    components: &mut Value,
+    version: i32,
+) -> Result<(), UnsupportedSchemaVersion> {
+    if !(1..=CURRENT_SCHEMA_VERSION).contains(&version) {
+        return Err(UnsupportedSchemaVersion(version));
+    }
+
+    for upgrade in &UPGRADES[(version - 1) as usize..] {
+        upgrade(components);
+    }
+    Ok(())
+}
+
+/// Version 2 only accepts the callout styles the renderer knows. Version 1
+/// stored any string and rendered unknown ones as a note, so map common
+/// aliases to their style and everything else to `note`.
+fn v1_to_v2(components: &mut Value) {
+    for_each_component(components, &mut |component| {
+        if component.get("type").and_then(Value::as_str) != Some("callout") {
+            return;
+        }
+
+        let style = component
+            .get("style")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let style = match style.as_str() {
+            "info" | "warning" | "success" | "error" | "note" => style.as_str(),
+            "tip" | "hint" => "info",
+            "warn" | "caution" => "warning",
+            "danger" | "err" => "error",
+            "ok" | "done" => "success",
+            _ => "note",
+        };
+        component.insert("style".to_string(), Value::from(style));
+    });
+}
+
+/// Call `f` on every component object in a `components` array, including
+/// components nested inside `tabs`, `details` and `steps`.
+fn for_each_component(components: &mut Value, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    let Some(items) = components.as_array_mut() else {
+        return;
+    };
+
+    for item in items {
+        let Some(component) = item.as_object_mut() else {
+            continue;
+        };
+        f(component);
+
+        if let Some(nested) = component.get_mut("components") {
+            for_each_component(nested, f);
+        }
+        for key in ["tabs", "steps"] {
+            if let Some(Value::Array(children)) = component.get_mut(key) {
+                for child in children {
+                    if let Some(nested) = child.get_mut("components") {
+                        for_each_component(nested, f);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn current_version_is_left_alone() {
+        let mut components = json!([{ "type": "callout", "style": "danger", "markdown": "x" }]);
+        let before = components.clone();
+
+        upgrade_components(&mut components, CURRENT_SCHEMA_VERSION).unwrap();
+
+        assert_eq!(components, before);
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let mut components = json!([]);
+
+        assert!(upgrade_components(&mut components, 0).is_err());
+        assert!(upgrade_components(&mut components, CURRENT_SCHEMA_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn v1_to_v2_normalizes_callout_styles() {
+        let mut components = json!([
+            { "type": "callout", "style": "Warn", "markdown": "a" },
+            { "type": "callout", "style": "danger", "markdown": "b" },
+            { "type": "callout", "style": "tip", "markdown": "c" },
+            { "type": "callout", "style": "success", "markdown": "d" },
+            { "type": "callout", "style": "sparkly", "markdown": "e" },
+            { "type": "callout", "markdown": "f" },
+            { "type": "heading", "style": "danger", "text": "g" },
+        ]);
+
+        v1_to_v2(&mut components);
+
+        let styles: Vec<&str> = components
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|component| component["style"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            styles,
+            ["warning", "error", "info", "success", "note", "note", "danger"]
+        );
+    }
+
+    #[test]
+    fn v1_to_v2_upgrades_nested_components() {
+        let mut components = json!([
+            {
+                "type": "tabs",
+                "tabs": [{
+                    "label": "One",
+                    "components": [{ "type": "callout", "style": "warn", "markdown": "a" }]
+                }]
+            },
+            {
+                "type": "details",
+                "summary": "More",
+                "components": [{ "type": "callout", "style": "danger", "markdown": "b" }]
+            },
+            {
+                "type": "steps",
+                "steps": [{
+                    "title": "First",
+                    "components": [{ "type": "callout", "style": "hint", "markdown": "c" }]
+                }]
+            }
+        ]);
+
+        upgrade_components(&mut components, 1).unwrap();
+
+        assert_eq!(
+            components[0]["tabs"][0]["components"][0]["style"],
+            "warning"
+        );
+        assert_eq!(components[1]["components"][0]["style"], "error");
+        assert_eq!(components[2]["steps"][0]["components"][0]["style"], "info");
+    }
+}
@@ -0,0 +1,166 @@
+//! Listing published posts a page at a time. Pages are keyset-paginated on
+//! `(published_at, id)`: each one ends with an opaque cursor naming the last
+//! post shown, and the next page starts after it. Unlike offsets, cursors
+//! neither skip nor repeat posts when new ones are published in between.
+
+use crate::models::blog::BlogPostSummary;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_PER_PAGE: usize = 10;
+/// Larger `per_page` values are lowered to this.
+pub const MAX_PER_PAGE: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PostSort {
+    /// Most recently published first.
+    #[default]
+    Newest,
+    Oldest,
+}
+
+impl PostSort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PostSort::Newest => "newest",
+            PostSort::Oldest => "oldest",
+        }
+    }
+
+    fn from_str(sort: &str) -> Option<Self> {
+        match sort {
+            "newest" => Some(PostSort::Newest),
+            "oldest" => Some(PostSort::Oldest),
+            _ => None,
+        }
+    }
+}
+
+/// Query string of the post list API, e.g.
+/// `?tag=rust&from=2025-01-01&sort=oldest&per_page=20`.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct PostListQuery {
+    /// `next_cursor` of the previous page; the first page when omitted.
+    pub cursor: Option<String>,
+    /// Posts per page: 10 unless given, at most 100.
+    pub per_page: Option<usize>,
+    #[serde(default)]
+    pub sort: PostSort,
+    /// Only posts with this tag.
+    pub tag: Option<String>,
+    /// ID of the user who wrote the posts.
+    pub author: Option<Uuid>,
+    /// First day of publication to include, in UTC.
+    pub from: Option<NaiveDate>,
+    /// Last day of publication to include, in UTC.
+    pub to: Option<NaiveDate>,
+    /// Only posts with (`true`) or without (`false`) a cover image.
+    pub has_cover: Option<bool>,
+}
+
+impl PostListQuery {
+    /// `per_page`, or the default, within `1..=MAX_PER_PAGE`.
+    pub fn per_page(&self) -> usize {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+/// Which published posts to list. Fields left unset match every post.
+#[derive(Debug, Clone, Default)]
+pub struct PostFilter {
+    pub tag: Option<String>,
+    pub author_id: Option<Uuid>,
+    /// Published at or after this instant.
+    pub published_from: Option<DateTime<Utc>>,
+    /// Published before this instant.
+    pub published_until: Option<DateTime<Utc>>,
+    pub has_cover: Option<bool>,
+}
+
+/// Whose posts a page lists, when pages of several are listed at once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PostOwner {
+    /// The posts the user with this ID wrote.
+    Author(Uuid),
+    /// The posts with this tag.
+    Tag(String),
+}
+
+/// The last post of a page, in the order it was listed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PostCursor {
+    pub sort: PostSort,
+    pub published_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl PostCursor {
+    /// The cursor after `post`, which must be published.
+    pub fn after(post: &BlogPostSummary, sort: PostSort) -> Option<Self> {
+        Some(PostCursor {
+            sort,
+            published_at: post.published_at?,
+            id: post.id,
+        })
+    }
+
+    /// URL-safe text that clients pass back unchanged.
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}.{}.{}",
+            self.sort.as_str(),
+            self.published_at.timestamp_micros(),
+            self.id
+        );
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// The cursor encoded as `text`, or `None` when it is not one.
+    pub fn decode(text: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(text).ok()?).ok()?;
+        let mut parts = raw.splitn(3, '.');
+        let sort = PostSort::from_str(parts.next()?)?;
+        let published_at = DateTime::from_timestamp_micros(parts.next()?.parse().ok()?)?;
+        let id = parts.next()?.parse().ok()?;
+        Some(PostCursor {
+            sort,
+            published_at,
+            id,
+        })
+    }
+}
+
+/// One page of posts. `next_cursor` is set when more posts follow.
+#[derive(Debug, Clone, Default)]
+pub struct PostPage {
+    pub posts: Vec<BlogPostSummary>,
+    pub per_page: usize,
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_round_trip() {
+        let cursor = PostCursor {
+            sort: PostSort::Oldest,
+            published_at: DateTime::from_timestamp_micros(1_751_328_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(PostCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(PostCursor::decode("not a cursor"), None);
+        assert_eq!(
+            PostCursor::decode(&URL_SAFE_NO_PAD.encode("newest.1.x")),
+            None
+        );
+    }
+}
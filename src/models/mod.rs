@@ -1 +1,6 @@
 pub mod blog;
+pub mod component_schema;
+pub mod listing;
+pub mod migration;
+pub mod redirect;
+pub mod user;
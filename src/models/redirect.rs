@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A redirect from one path of the site to another path or URL.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Redirect {
+    pub id: Uuid,
+    pub from_path: String,
+    pub to_path: String,
+    /// `301 Moved Permanently` when set, `302 Found` otherwise.
+    pub permanent: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedirectInput {
+    pub from_path: String,
+    pub to_path: String,
+    #[serde(default = "permanent_by_default")]
+    pub permanent: bool,
+}
+
+fn permanent_by_default() -> bool {
+    true
+}
@@ -1,6 +1,36 @@
 use crate::database::Database;
-use crate::models::blog::{BlogPost, BlogPostSummary};
+use crate::models::blog::{
+    unknown_components, ArchivePeriod, ArchiveYear, AuthorInput, BlogPost, BlogPostInput,
+    BlogPostSummary, FeaturedInput, FeaturedPost, Homepage, PostSource, RelatedCandidate,
+    SeriesSummary, SourceFile, TagSummary,
+};
+use crate::models::component_schema::upgrade_components;
+use crate::models::listing::{
+    PostCursor, PostFilter, PostListQuery, PostOwner, PostPage, PostSort,
+};
+use crate::services::export::{build_archive, SiteArchive};
+use crate::services::related::rank_related;
+use crate::services::slugs::{slugify, unique_slug};
+use crate::services::validation::{validate_post, ValidationErrors};
+use crate::telemetry::Telemetry;
 use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Number of related posts shown under a post and returned by the API.
+pub const RELATED_POSTS_LIMIT: usize = 3;
+/// Most other posts ranked against a post to find its related ones.
+pub const RELATED_CANDIDATES_LIMIT: usize = 200;
+/// How many of each kind of post the homepage shows.
+pub const HOMEPAGE_FEATURED_POSTS: usize = 3;
+pub const HOMEPAGE_LATEST_POSTS: usize = 6;
+pub const HOMEPAGE_SERIES: usize = 4;
+pub const HOMEPAGE_TAGS: usize = 12;
+/// Posts suggested on the 404 page.
+pub const SUGGESTED_POSTS_LIMIT: usize = 5;
+/// Results shown for a search.
+pub const SEARCH_RESULTS_LIMIT: usize = 20;
 
 pub struct BlogService {
     db: Database,
@@ -12,21 +42,580 @@ impl BlogService {
     }
 
     pub async fn get_post_by_slug(&self, slug: &str) -> Result<Option<BlogPost>> {
-        self.db.get_blog_post_by_slug(slug).await
+        let post = self.db.get_blog_post_by_slug(slug).await?;
+        if let Some(post) = &post {
+            warn_unknown_components(post);
+        }
+        Ok(post)
     }
 
-    pub async fn list_posts(
+    /// Any post, published or not, e.g. for previewing or exporting it.
+    pub async fn get_post_by_id(&self, id: Uuid) -> Result<Option<BlogPost>> {
+        let post = self.db.get_blog_post_by_id(id).await?;
+        if let Some(post) = &post {
+            warn_unknown_components(post);
+        }
+        Ok(post)
+    }
+
+    /// Any post with `slug`, published or not.
+    pub async fn get_any_post_by_slug(&self, slug: &str) -> Result<Option<BlogPost>> {
+        let post = self.db.get_any_blog_post_by_slug(slug).await?;
+        if let Some(post) = &post {
+            warn_unknown_components(post);
+        }
+        Ok(post)
+    }
+
+    /// Current slug of the published post that used to be at `old_slug`,
+    /// for redirecting old links.
+    pub async fn current_slug(&self, old_slug: &str) -> Result<Option<String>> {
+        self.db.get_current_slug(old_slug).await
+    }
+
+    /// Every post, drafts included, oldest first.
+    pub async fn list_all_posts(&self) -> Result<Vec<BlogPost>> {
+        self.db.list_all_blog_posts().await
+    }
+
+    /// Post counts per year and month, newest first.
+    pub async fn archive(&self) -> Result<Vec<ArchiveYear>> {
+        self.db.get_archive().await
+    }
+
+    /// A page of the posts published in `period`, and how many there are
+    /// in all. `None` for a month that doesn't exist.
+    pub async fn list_posts_in(
         &self,
+        period: ArchivePeriod,
         page: usize,
         per_page: usize,
-    ) -> Result<(Vec<BlogPostSummary>, i64)> {
-        let offset = (page.saturating_sub(1)) * per_page;
+    ) -> Result<Option<(Vec<BlogPostSummary>, i64)>> {
+        let Some((start, end)) = period.range() else {
+            return Ok(None);
+        };
+        let filter = PostFilter {
+            published_from: Some(start),
+            published_until: Some(end),
+            ..PostFilter::default()
+        };
+        let (posts, total) = self
+            .db
+            .list_blog_posts_counted(&filter, per_page as i64, page_offset(page, per_page))
+            .await?;
+        Ok(Some((posts, total)))
+    }
+
+    /// Featured posts, newest posts, active series and popular tags, read
+    /// concurrently.
+    pub async fn homepage(&self) -> Result<Homepage> {
+        let (featured, latest, series, tags) = tokio::try_join!(
+            self.db
+                .list_featured_blog_posts(true, HOMEPAGE_FEATURED_POSTS as i64),
+            // Enough to fill the section after leaving out featured posts.
+            self.db
+                .list_blog_posts((HOMEPAGE_LATEST_POSTS + HOMEPAGE_FEATURED_POSTS) as i64, 0),
+            self.db.list_series_summaries(HOMEPAGE_SERIES as i64),
+            self.db.list_tag_summaries(HOMEPAGE_TAGS as i64),
+        )?;
+
+        let featured: Vec<BlogPostSummary> = featured.into_iter().map(|f| f.post).collect();
+        let latest = latest
+            .into_iter()
+            .filter(|post| featured.iter().all(|f| f.id != post.id))
+            .take(HOMEPAGE_LATEST_POSTS)
+            .collect();
+
+        Ok(Homepage {
+            featured,
+            latest,
+            series,
+            tags,
+        })
+    }
+
+    /// Tags of published posts, the most used first.
+    pub async fn tags(&self, limit: usize) -> Result<Vec<TagSummary>> {
+        self.db.list_tag_summaries(limit as i64).await
+    }
+
+    /// Series with published posts, the one with the newest part first.
+    pub async fn series(&self, limit: usize) -> Result<Vec<SeriesSummary>> {
+        self.db.list_series_summaries(limit as i64).await
+    }
+
+    /// Feature the post with `id` on the homepage at `position`, or stop
+    /// featuring it. Returns `None` when there is no such post.
+    pub async fn set_featured(
+        &self,
+        id: Uuid,
+        input: &FeaturedInput,
+    ) -> Result<Option<FeaturedPost>> {
+        self.db
+            .set_blog_post_featured(id, input.featured, input.position)
+            .await
+    }
+
+    /// Credit the post with `id` to the user in `input`, or to nobody.
+    /// Returns `None` when there is no such post; an unknown user is a
+    /// [`ValidationErrors`].
+    pub async fn set_author(
+        &self,
+        id: Uuid,
+        input: &AuthorInput,
+    ) -> Result<Option<BlogPostSummary>> {
+        if let Some(author_id) = input.author_id {
+            if self.db.get_user_by_id(author_id).await?.is_none() {
+                return Err(
+                    ValidationErrors::single("$.author_id", "is not the ID of a user").into(),
+                );
+            }
+        }
+        self.db.set_blog_post_author(id, input.author_id).await
+    }
+
+    /// Featured posts in homepage order, drafts included.
+    pub async fn list_featured(&self) -> Result<Vec<FeaturedPost>> {
+        self.db.list_featured_blog_posts(false, i64::MAX).await
+    }
+
+    /// Published posts with a slug like `slug`, to suggest when it leads
+    /// nowhere.
+    pub async fn similar_posts(&self, slug: &str, limit: usize) -> Result<Vec<BlogPostSummary>> {
+        self.db
+            .get_posts_with_similar_slug(slug, limit as i64)
+            .await
+    }
+
+    /// Published posts whose title matches `query`, best match first.
+    pub async fn search_posts(&self, query: &str, limit: usize) -> Result<Vec<BlogPostSummary>> {
+        self.db.search_blog_posts(query.trim(), limit as i64).await
+    }
+
+    /// A page of published posts matching `query`. A cursor that is
+    /// malformed or was issued for another sort, and a date range ending
+    /// before it starts, are [`ValidationErrors`].
+    pub async fn list_posts_page(&self, query: &PostListQuery) -> Result<PostPage> {
+        let cursor = parse_cursor(query.cursor.as_deref(), query.sort)?;
+        let filter = post_filter(query)?;
+        let per_page = query.per_page();
+
+        // One more than asked for tells whether another page follows.
+        let posts = self
+            .db
+            .list_blog_posts_page(&filter, query.sort, cursor.as_ref(), per_page as i64 + 1)
+            .await?;
+        Ok(page_of(posts, per_page, query.sort))
+    }
+
+    /// A page of the published posts of each of `owners`, all after the same
+    /// `cursor`, read in one query.
+    pub async fn list_post_pages(
+        &self,
+        owners: &[PostOwner],
+        sort: PostSort,
+        cursor: Option<&PostCursor>,
+        per_page: usize,
+    ) -> Result<HashMap<PostOwner, PostPage>> {
         let posts = self
             .db
-            .list_blog_posts(per_page as i64, offset as i64)
+            .list_blog_post_pages(owners, sort, cursor, per_page as i64 + 1)
+            .await?;
+
+        let mut owned: HashMap<PostOwner, Vec<BlogPostSummary>> = owners
+            .iter()
+            .map(|owner| (owner.clone(), Vec::new()))
+            .collect();
+        for (owner, post) in posts {
+            owned.entry(owner).or_default().push(post);
+        }
+        Ok(owned
+            .into_iter()
+            .map(|(owner, posts)| (owner, page_of(posts, per_page, sort)))
+            .collect())
+    }
+
+    /// A page of the published posts, newest first, and how many there are
+    /// in all.
+    pub async fn list_posts(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<(Vec<BlogPostSummary>, i64)> {
+        self.db
+            .list_blog_posts_counted(
+                &PostFilter::default(),
+                per_page as i64,
+                page_offset(page, per_page),
+            )
+            .await
+    }
+
+    /// Validate and store a new post.
+    ///
+    /// Validation failures are returned as
+    /// [`ValidationErrors`](crate::services::validation::ValidationErrors)
+    /// inside the `anyhow::Error` so handlers can report them as such.
+    ///
+    /// A post without a slug gets one generated from its title, suffixed
+    /// with `-2`, `-3`, ... when a post has or used to have it. A slug that
+    /// is already taken is a validation failure.
+    pub async fn create_post(&self, input: &BlogPostInput) -> Result<BlogPost> {
+        self.insert_post(input, None).await
+    }
+
+    async fn insert_post(
+        &self,
+        input: &BlogPostInput,
+        source: Option<SourceFile<'_>>,
+    ) -> Result<BlogPost> {
+        validate_post(input)?;
+        let post = if input.slug.is_empty() {
+            let input = BlogPostInput {
+                slug: self.free_slug(&slugify(&input.title)).await?,
+                ..input.clone()
+            };
+            self.db.insert_blog_post(&input, source).await
+        } else {
+            self.db.insert_blog_post(input, source).await
+        };
+        let post = match post {
+            Ok(post) => post,
+            Err(error) => return Err(self.slug_taken_error(error, input).await),
+        };
+        self.refresh_related_posts(&post).await;
+        Ok(post)
+    }
+
+    /// Validate and store every post whose slug is not taken yet, leaving
+    /// existing posts alone. Returns how many posts were created.
+    pub async fn create_missing_posts(&self, inputs: &[BlogPostInput]) -> Result<usize> {
+        for input in inputs {
+            validate_post(input)?;
+        }
+        self.db.insert_missing_blog_posts(inputs).await
+    }
+
+    pub async fn update_post(&self, id: Uuid, input: &BlogPostInput) -> Result<Option<BlogPost>> {
+        self.update_existing_post(id, input, None).await
+    }
+
+    async fn update_existing_post(
+        &self,
+        id: Uuid,
+        input: &BlogPostInput,
+        source: Option<SourceFile<'_>>,
+    ) -> Result<Option<BlogPost>> {
+        validate_post(input)?;
+        let post = match self.db.update_blog_post(id, input, source).await {
+            Ok(post) => post,
+            Err(error) => return Err(self.slug_taken_error(error, input).await),
+        };
+        if let Some(post) = &post {
+            self.refresh_related_posts(post).await;
+        }
+        Ok(post)
+    }
+
+    /// `base`, or the first of `base-2`, `base-3`, ... that no post has or
+    /// used to have, so old links never lead to the new post.
+    async fn free_slug(&self, base: &str) -> Result<String> {
+        let prefix = base.split('-').next().unwrap_or(base);
+        let taken = self.db.get_slugs_with_prefix(prefix).await?;
+        Ok(unique_slug(base, |slug| {
+            taken.iter().any(|taken| taken == slug)
+        }))
+    }
+
+    /// Report a save that hit the unique slug constraint as a validation
+    /// failure suggesting a free slug; other errors are returned as-is.
+    async fn slug_taken_error(&self, error: anyhow::Error, input: &BlogPostInput) -> anyhow::Error {
+        let taken = error
+            .downcast_ref::<sqlx::Error>()
+            .and_then(|error| error.as_database_error())
+            .is_some_and(|error| {
+                error.is_unique_violation() && error.constraint() == Some("blog_posts_slug_key")
+            });
+        if !taken {
+            return error;
+        }
+
+        let slug = if input.slug.is_empty() {
+            slugify(&input.title)
+        } else {
+            input.slug.clone()
+        };
+        let message = match self.free_slug(&slug).await {
+            Ok(free) => format!(
+                "`{}` is already used by another post; `{}` is free",
+                slug, free
+            ),
+            Err(_) => format!("`{}` is already used by another post", slug),
+        };
+        ValidationErrors::single("$.slug", message).into()
+    }
+
+    /// Publish the post with `slug` at `published_at`, or now if it is a
+    /// draft and no date is given. Returns `None` when there is no such post.
+    pub async fn publish_post(
+        &self,
+        slug: &str,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<BlogPost>> {
+        let post = self.db.publish_blog_post(slug, published_at).await?;
+        if let Some(post) = &post {
+            self.refresh_related_posts(post).await;
+        }
+        Ok(post)
+    }
+
+    pub async fn unpublish_post(&self, slug: &str) -> Result<Option<BlogPost>> {
+        self.db.unpublish_blog_post(slug).await
+    }
+
+    /// Delete the post with `slug`. Returns whether there was one.
+    pub async fn delete_post(&self, slug: &str) -> Result<bool> {
+        self.db.delete_blog_post(slug).await
+    }
+
+    /// Sync state of the post synced from `path`, or else of the post
+    /// using `slug`.
+    pub async fn post_source(&self, path: &str, slug: &str) -> Result<Option<PostSource>> {
+        self.db.get_post_source(path, slug).await
+    }
+
+    pub async fn post_sources(&self) -> Result<Vec<PostSource>> {
+        self.db.list_post_sources().await
+    }
+
+    /// Create or update (when `id` is given) a post from `source`, recording
+    /// the file and its contents hash for the next sync in the same
+    /// statement.
+    pub async fn save_synced_post(
+        &self,
+        id: Option<Uuid>,
+        input: &BlogPostInput,
+        source: SourceFile<'_>,
+    ) -> Result<Option<BlogPost>> {
+        match id {
+            Some(id) => self.update_existing_post(id, input, Some(source)).await,
+            None => self.insert_post(input, Some(source)).await.map(Some),
+        }
+    }
+
+    pub async fn export_archive(&self) -> Result<SiteArchive> {
+        let posts = self.list_all_posts().await?;
+        let featured = self.db.list_featured_entries().await?;
+        let slug_history = self.db.list_slug_history().await?;
+        let redirects = self.db.list_redirects().await?;
+        Ok(build_archive(posts, featured, slug_history, redirects))
+    }
+
+    /// Restore everything in an archive read by
+    /// [`parse_archive`](crate::services::export::parse_archive),
+    /// overwriting posts with the same id and redirects from the same path.
+    /// Returns the number of posts restored.
+    pub async fn import_archive(&self, archive: &SiteArchive) -> Result<usize> {
+        self.db
+            .restore_archive(
+                &archive.posts,
+                &archive.featured,
+                &archive.slug_history,
+                &archive.redirects,
+            )
             .await?;
+        Ok(archive.posts.len())
+    }
+
+    /// Rewrite every post stored with an older component schema version in
+    /// the current one. Returns how many posts were upgraded.
+    ///
+    /// Reads upgrade old posts on the fly, so this is only needed to stop
+    /// paying for that on every read.
+    pub async fn upgrade_all_components(&self) -> Result<usize> {
+        let mut upgraded = 0;
+        for (id, version, mut components) in self.db.list_outdated_components().await? {
+            upgrade_components(&mut components, version)?;
+            if self
+                .db
+                .store_upgraded_components(id, version, &components)
+                .await?
+            {
+                upgraded += 1;
+            }
+        }
+        Ok(upgraded)
+    }
+
+    /// Recompute the related posts of a freshly saved post so its first
+    /// reader doesn't pay for it. Other posts are recomputed lazily.
+    async fn refresh_related_posts(&self, post: &BlogPost) {
+        if post.published_at.is_none() {
+            return;
+        }
+        if let Err(error) = self.related_posts(post).await {
+            tracing::warn!(slug = %post.slug, "Failed to refresh related posts: {:?}", error);
+        }
+    }
+
+    /// Related posts for `post`, served from the cache when available.
+    pub async fn related_posts(&self, post: &BlogPost) -> Result<Vec<BlogPostSummary>> {
+        let mut related = self.related_posts_for(&[post.id]).await?;
+        Ok(related.remove(&post.id).unwrap_or_default())
+    }
+
+    /// Related posts for each of `post_ids`, served from the cache when
+    /// available.
+    ///
+    /// Only the posts sharing the most tags with a post, up to
+    /// [`RELATED_CANDIDATES_LIMIT`], are ranked against it. Database
+    /// triggers drop the cached rankings a change to a post's title, tags,
+    /// series, components or publication can move, so a miss recomputes the
+    /// ranking and stores it for the next reader.
+    pub async fn related_posts_for(
+        &self,
+        post_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<BlogPostSummary>>> {
+        let mut related_ids = self.db.get_cached_related_ids_for(post_ids).await?;
+
+        let missing: Vec<Uuid> = post_ids
+            .iter()
+            .filter(|id| !related_ids.contains_key(id))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            let mut candidates: HashMap<Uuid, Vec<RelatedCandidate>> = HashMap::new();
+            for (id, candidate) in self
+                .db
+                .list_related_candidates(&missing, RELATED_CANDIDATES_LIMIT as i64)
+                .await?
+            {
+                candidates.entry(id).or_default().push(candidate);
+            }
+            let ranked = Telemetry::spawn_blocking_with_tracing(move || {
+                missing
+                    .into_iter()
+                    .map(|id| {
+                        let candidates = candidates.remove(&id).unwrap_or_default();
+                        (id, rank_related(id, &candidates, RELATED_POSTS_LIMIT))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+
+            for (id, ids) in ranked {
+                self.db.store_related_ids(id, &ids).await?;
+                related_ids.insert(id, ids);
+            }
+        }
+
+        let all_ids: Vec<Uuid> = related_ids.values().flatten().copied().collect();
+        let summaries: HashMap<Uuid, BlogPostSummary> = self
+            .db
+            .get_blog_post_summaries(&all_ids)
+            .await?
+            .into_iter()
+            .map(|post| (post.id, post))
+            .collect();
+
+        Ok(related_ids
+            .into_iter()
+            .map(|(id, ids)| {
+                let posts = ids
+                    .iter()
+                    .filter_map(|id| summaries.get(id).cloned())
+                    .collect();
+                (id, posts)
+            })
+            .collect())
+    }
+}
+
+/// The cursor a client passed as `text`, which must have been issued for
+/// `sort`. No cursor, or an empty one, starts at the first page.
+pub fn parse_cursor(
+    text: Option<&str>,
+    sort: PostSort,
+) -> Result<Option<PostCursor>, ValidationErrors> {
+    let Some(text) = text.filter(|text| !text.is_empty()) else {
+        return Ok(None);
+    };
+    match PostCursor::decode(text) {
+        Some(cursor) if cursor.sort == sort => Ok(Some(cursor)),
+        Some(cursor) => {
+            let message = format!("was issued for sort={}", cursor.sort.as_str());
+            Err(ValidationErrors::single("$.cursor", message))
+        }
+        None => {
+            let message = "is not a cursor returned by this API";
+            Err(ValidationErrors::single("$.cursor", message))
+        }
+    }
+}
+
+/// A page of `per_page` posts out of `posts`, which were listed with one
+/// more than that to tell whether another page follows.
+fn page_of(mut posts: Vec<BlogPostSummary>, per_page: usize, sort: PostSort) -> PostPage {
+    let next_cursor = if posts.len() > per_page {
+        posts.truncate(per_page);
+        posts
+            .last()
+            .and_then(|post| PostCursor::after(post, sort))
+            .map(|cursor| cursor.encode())
+    } else {
+        None
+    };
+    PostPage {
+        posts,
+        per_page,
+        next_cursor,
+    }
+}
+
+/// The posts `query` asks for. Its dates are whole UTC days, both included.
+fn post_filter(query: &PostListQuery) -> Result<PostFilter, ValidationErrors> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if to < from {
+            return Err(ValidationErrors::single(
+                "$.to",
+                "must not be before `from`",
+            ));
+        }
+    }
+    let start_of = |day: NaiveDate| day.and_time(NaiveTime::MIN).and_utc();
+
+    Ok(PostFilter {
+        tag: query
+            .tag
+            .as_deref()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string),
+        author_id: query.author,
+        published_from: query.from.map(start_of),
+        published_until: query.to.and_then(|to| to.succ_opt()).map(start_of),
+        has_cover: query.has_cover,
+    })
+}
+
+/// How many posts come before `page`. A page too far out to count is past
+/// the last one, so it lists nothing and handlers redirect it.
+fn page_offset(page: usize, per_page: usize) -> i64 {
+    page.saturating_sub(1)
+        .checked_mul(per_page)
+        .and_then(|offset| i64::try_from(offset).ok())
+        .unwrap_or(i64::MAX)
+}
 
-        let total = self.db.count_blog_posts().await?;
-        Ok((posts, total))
+/// Components that failed to deserialize are skipped when rendering, so make
+/// sure they don't go unnoticed.
+fn warn_unknown_components(post: &BlogPost) {
+    for (index, unknown) in unknown_components(&post.components) {
+        tracing::warn!(
+            slug = %post.slug,
+            component = index,
+            "Skipping malformed component: {}",
+            unknown.error
+        );
     }
 }
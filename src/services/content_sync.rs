@@ -0,0 +1,423 @@
+//! Posts written as files: every `.md` (Markdown with front matter) and
+//! `.json` (the admin API's post format) file under the content directory is
+//! synced into `blog_posts`, at startup and whenever a file changes.
+//!
+//! A post remembers the file it came from, the hash of the contents last
+//! synced and when that was. A file whose post has been edited in the
+//! database since is reported as a conflict and left alone unless the sync
+//! is forced, in which case the file wins. Publishing, unpublishing,
+//! featuring or crediting a post is not an edit. Deleting a file never
+//! deletes its post.
+
+use crate::models::blog::{BlogPostInput, PostSource, SourceFile};
+use crate::services::blog::BlogService;
+use crate::services::markdown_import::{post_from_markdown, MarkdownImportOptions};
+use crate::services::slugs::to_slug;
+use crate::services::validation::{parse_post_input, ValidationErrors};
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How long to wait for a burst of file events (an editor saving, a `git
+/// pull`) to settle before syncing.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    /// Slugs of posts created from new files.
+    pub created: Vec<String>,
+    /// Slugs of posts updated from changed files.
+    pub updated: Vec<String>,
+    pub unchanged: usize,
+    pub conflicts: Vec<SyncIssue>,
+    /// Files that could not be read or are not valid posts.
+    pub errors: Vec<SyncIssue>,
+    /// Slugs of synced posts whose file no longer exists.
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncIssue {
+    pub path: String,
+    pub message: String,
+}
+
+pub struct ContentSync {
+    blog_service: Arc<BlogService>,
+    dir: PathBuf,
+}
+
+impl ContentSync {
+    pub fn new(blog_service: Arc<BlogService>, dir: PathBuf) -> Self {
+        Self { blog_service, dir }
+    }
+
+    /// Sync every file in the content directory. With `force`, files
+    /// overwrite conflicting database edits and adopt posts of the same slug
+    /// created outside the directory.
+    pub async fn sync(&self, force: bool) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut seen = HashSet::new();
+
+        for path in content_files(&self.dir)? {
+            let relative = relative_path(&self.dir, &path);
+            seen.insert(relative.clone());
+
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(error) => {
+                    report.errors.push(issue(&relative, error.to_string()));
+                    continue;
+                }
+            };
+            let input = match parse_post_file(&path, &source) {
+                Ok(input) => input,
+                Err(errors) => {
+                    report.errors.push(issue(&relative, errors.to_string()));
+                    continue;
+                }
+            };
+
+            self.sync_file(&relative, &source, &input, force, &mut report)
+                .await?;
+        }
+
+        for source in self.blog_service.post_sources().await? {
+            if source.source_path.is_some_and(|path| !seen.contains(&path)) {
+                report.missing.push(source.slug);
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn sync_file(
+        &self,
+        path: &str,
+        source: &str,
+        input: &BlogPostInput,
+        force: bool,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let hash = format!("{:x}", Sha256::digest(source.as_bytes()));
+
+        let existing = self.blog_service.post_source(path, &input.slug).await?;
+        let id = match plan(existing.as_ref(), path, &hash, force) {
+            SyncAction::Unchanged => {
+                report.unchanged += 1;
+                return Ok(());
+            }
+            SyncAction::Create => None,
+            SyncAction::Update(id) => Some(id),
+            SyncAction::Conflict(message) => {
+                report.conflicts.push(issue(path, message));
+                return Ok(());
+            }
+        };
+
+        match self
+            .blog_service
+            .save_synced_post(id, input, SourceFile { path, hash: &hash })
+            .await
+        {
+            Ok(Some(post)) if id.is_some() => report.updated.push(post.slug),
+            Ok(Some(post)) => report.created.push(post.slug),
+            Ok(None) => {}
+            Err(error) => report.errors.push(issue(path, error.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Sync whenever something in the content directory changes, for as long
+    /// as the server runs.
+    pub fn watch(self: Arc<Self>) -> Result<()> {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+        watcher.watch(&self.dir, RecursiveMode::Recursive)?;
+
+        tokio::spawn(async move {
+            // Keep the watcher alive for as long as the task runs.
+            let _watcher = watcher;
+
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    // Reading the files while syncing is an event too.
+                    Ok(event) if !changes_files(&event) => continue,
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::warn!("Content directory watch error: {:?}", error);
+                        continue;
+                    }
+                }
+
+                tokio::time::sleep(DEBOUNCE).await;
+                while receiver.try_recv().is_ok() {}
+
+                match self.sync(false).await {
+                    Ok(report) => log_report(&report),
+                    Err(error) => tracing::error!("Failed to sync content directory: {:?}", error),
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn changes_files(event: &notify::Event) -> bool {
+    event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()
+}
+
+#[derive(Debug, PartialEq)]
+enum SyncAction {
+    Unchanged,
+    Create,
+    Update(Uuid),
+    Conflict(String),
+}
+
+/// What to do with the file at `path`, given the post it would be saved to.
+fn plan(existing: Option<&PostSource>, path: &str, hash: &str, force: bool) -> SyncAction {
+    let Some(existing) = existing else {
+        return SyncAction::Create;
+    };
+    match conflict(existing, path, hash) {
+        None if existing.source_hash.as_deref() == Some(hash) => SyncAction::Unchanged,
+        None => SyncAction::Update(existing.id),
+        // Forcing never takes over a post synced from another file.
+        Some(_) if force && existing.source_path.as_deref().is_none_or(|p| p == path) => {
+            SyncAction::Update(existing.id)
+        }
+        Some(message) => SyncAction::Conflict(message),
+    }
+}
+
+/// Why the file at `path` may not overwrite `existing`, if it may not.
+fn conflict(existing: &PostSource, path: &str, hash: &str) -> Option<String> {
+    match existing.source_path.as_deref() {
+        Some(source_path) if source_path != path => Some(format!(
+            "slug `{}` is already used by {}",
+            existing.slug, source_path
+        )),
+        None => Some(format!(
+            "a post with slug `{}` was created outside the content directory",
+            existing.slug
+        )),
+        Some(_)
+            if existing
+                .source_synced_at
+                .is_none_or(|synced_at| existing.content_updated_at > synced_at) =>
+        {
+            let message = if existing.source_hash.as_deref() == Some(hash) {
+                "was edited in the database since it was last synced"
+            } else {
+                "changed both in the file and in the database since it was last synced"
+            };
+            Some(format!("post `{}` {}", existing.slug, message))
+        }
+        Some(_) => None,
+    }
+}
+
+pub fn log_report(report: &SyncReport) {
+    tracing::info!(
+        created = report.created.len(),
+        updated = report.updated.len(),
+        unchanged = report.unchanged,
+        conflicts = report.conflicts.len(),
+        errors = report.errors.len(),
+        "Synced content directory"
+    );
+    for conflict in &report.conflicts {
+        tracing::warn!(path = %conflict.path, "Content sync conflict: {}", conflict.message);
+    }
+    for error in &report.errors {
+        tracing::warn!(path = %error.path, "Skipping content file: {}", error.message);
+    }
+    for slug in &report.missing {
+        tracing::warn!(slug = %slug, "File of synced post no longer exists");
+    }
+}
+
+/// Parse a post file, either `.json` in the admin API's post format or
+/// Markdown with front matter. The slug defaults to the file name, made
+/// into a valid slug if it isn't one.
+pub fn parse_post_file(path: &Path, source: &str) -> Result<BlogPostInput, ValidationErrors> {
+    let stem = path
+        .file_stem()
+        .map(|stem| to_slug(&stem.to_string_lossy()));
+
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        let mut value: Value = serde_json::from_str(source)
+            .map_err(|e| ValidationErrors::single("$", e.to_string()))?;
+        if let (Some(object), Some(stem)) = (value.as_object_mut(), stem) {
+            object.entry("slug").or_insert(Value::String(stem));
+        }
+        parse_post_input(value)
+    } else {
+        let options = MarkdownImportOptions {
+            slug: stem,
+            dry_run: false,
+        };
+        post_from_markdown(source, &options)
+    }
+}
+
+/// Every `.md` and `.json` file below `dir`, skipping hidden entries, in a
+/// stable order.
+fn content_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+
+        if path.is_dir() {
+            files.extend(content_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "md" || extension == "json")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn relative_path(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn issue(path: &str, message: impl Into<String>) -> SyncIssue {
+    SyncIssue {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+
+    const PATH: &str = "rust/ownership.md";
+    const HASH: &str = "3f0c";
+
+    fn synced_at() -> DateTime<Utc> {
+        "2025-06-07T08:00:00Z".parse().unwrap()
+    }
+
+    /// A post last synced from `PATH` with contents hashing to `HASH`, and
+    /// not changed since.
+    fn synced() -> PostSource {
+        PostSource {
+            id: Uuid::nil(),
+            slug: "ownership".to_string(),
+            source_path: Some(PATH.to_string()),
+            source_hash: Some(HASH.to_string()),
+            content_updated_at: synced_at(),
+            source_synced_at: Some(synced_at()),
+        }
+    }
+
+    fn edited_in_the_database() -> PostSource {
+        PostSource {
+            content_updated_at: synced_at() + Duration::minutes(5),
+            ..synced()
+        }
+    }
+
+    fn is_conflict(action: SyncAction) -> bool {
+        matches!(action, SyncAction::Conflict(_))
+    }
+
+    #[test]
+    fn new_files_create_posts() {
+        assert_eq!(plan(None, PATH, HASH, false), SyncAction::Create);
+    }
+
+    #[test]
+    fn unchanged_files_are_skipped() {
+        assert_eq!(
+            plan(Some(&synced()), PATH, HASH, false),
+            SyncAction::Unchanged
+        );
+    }
+
+    #[test]
+    fn changed_files_update_their_post() {
+        assert_eq!(
+            plan(Some(&synced()), PATH, "a1b2", false),
+            SyncAction::Update(Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn posts_edited_in_the_database_are_conflicts() {
+        let post = edited_in_the_database();
+        let SyncAction::Conflict(message) = plan(Some(&post), PATH, HASH, false) else {
+            panic!("expected a conflict");
+        };
+        assert!(message.contains("edited in the database"), "{}", message);
+
+        let SyncAction::Conflict(message) = plan(Some(&post), PATH, "a1b2", false) else {
+            panic!("expected a conflict");
+        };
+        assert!(
+            message.contains("both in the file and in the database"),
+            "{}",
+            message
+        );
+
+        assert_eq!(
+            plan(Some(&post), PATH, "a1b2", true),
+            SyncAction::Update(Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn posts_of_other_files_are_never_taken_over() {
+        let post = PostSource {
+            source_path: Some("drafts/ownership.md".to_string()),
+            ..synced()
+        };
+        assert!(is_conflict(plan(Some(&post), PATH, HASH, false)));
+        assert!(is_conflict(plan(Some(&post), PATH, HASH, true)));
+    }
+
+    #[test]
+    fn posts_created_elsewhere_are_only_adopted_by_force() {
+        let post = PostSource {
+            source_path: None,
+            source_hash: None,
+            source_synced_at: None,
+            ..synced()
+        };
+        assert!(is_conflict(plan(Some(&post), PATH, HASH, false)));
+        assert_eq!(
+            plan(Some(&post), PATH, HASH, true),
+            SyncAction::Update(Uuid::nil())
+        );
+    }
+}
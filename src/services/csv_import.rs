@@ -0,0 +1,223 @@
+use crate::models::blog::{BlogComponent, ColumnAlignment};
+use crate::services::validation::ValidationErrors;
+use serde::Deserialize;
+
+/// Options for turning an uploaded CSV file into a `table` component,
+/// taken from the query string of the upload request.
+#[derive(Debug, Default, Deserialize)]
+pub struct CsvTableOptions {
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub sortable: bool,
+    /// Comma-separated column alignments, e.g. `left,right,right`.
+    pub alignment: Option<String>,
+    /// Separator byte; detected from the header line when omitted.
+    pub delimiter: Option<char>,
+}
+
+/// Separators detected when none is given, the first winning a tie.
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Build a `table` component from CSV data whose first record holds the
+/// column headers.
+pub fn table_from_csv(
+    data: &[u8],
+    options: &CsvTableOptions,
+) -> Result<BlogComponent, ValidationErrors> {
+    let delimiter = match options.delimiter {
+        None => detect_delimiter(data),
+        Some(c) if c.is_ascii() => c as u8,
+        Some(_) => {
+            return Err(ValidationErrors::single(
+                "delimiter",
+                "must be an ASCII character",
+            ))
+        }
+    };
+
+    let alignment = match &options.alignment {
+        None => Vec::new(),
+        Some(names) => names
+            .split(',')
+            .map(|name| {
+                ColumnAlignment::from_name(name).ok_or_else(|| {
+                    ValidationErrors::single(
+                        "alignment",
+                        format!("unknown alignment `{}`", name.trim()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| ValidationErrors::single("csv", e.to_string()))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    if headers.iter().all(String::is_empty) {
+        return Err(ValidationErrors::single(
+            "csv",
+            "must start with a row of column headers",
+        ));
+    }
+
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|e| ValidationErrors::single("csv", e.to_string()))
+        })
+        .collect::<Result<Vec<Vec<String>>, _>>()?;
+
+    Ok(BlogComponent::Table {
+        headers,
+        rows,
+        alignment,
+        caption: options.caption.clone().filter(|c| !c.trim().is_empty()),
+        sortable: options.sortable,
+    })
+}
+
+/// The separator the header line of `data` uses most, outside quotes.
+fn detect_delimiter(data: &[u8]) -> u8 {
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut quoted = false;
+    for &byte in data {
+        match byte {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => break,
+            _ if !quoted => {
+                if let Some(index) = DELIMITERS.iter().position(|&d| d == byte) {
+                    counts[index] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut best = 0;
+    for (index, &count) in counts.iter().enumerate() {
+        if count > counts[best] {
+            best = index;
+        }
+    }
+    DELIMITERS[best]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::validation::{validate_components, MAX_TEXT_LENGTH};
+
+    fn table(data: &str, options: &CsvTableOptions) -> (Vec<String>, Vec<Vec<String>>) {
+        match table_from_csv(data.as_bytes(), options).unwrap() {
+            BlogComponent::Table { headers, rows, .. } => (headers, rows),
+            component => panic!("not a table: {:?}", component),
+        }
+    }
+
+    #[test]
+    fn detects_the_delimiter_of_the_header_line() {
+        let options = CsvTableOptions::default();
+        for data in [
+            "name,price\nTea,3\n",
+            "name;price\nTea;3\n",
+            "name\tprice\nTea\t3\n",
+            "name|price\nTea|3\n",
+        ] {
+            let (headers, rows) = table(data, &options);
+            assert_eq!(headers, ["name", "price"], "{:?}", data);
+            assert_eq!(rows, [["Tea", "3"]], "{:?}", data);
+        }
+
+        // Separators inside quotes don't count, and later lines don't either.
+        let (headers, _) = table("\"a;b\",c\n1;2;3,4\n", &options);
+        assert_eq!(headers, ["a;b", "c"]);
+    }
+
+    #[test]
+    fn a_given_delimiter_wins() {
+        let options = CsvTableOptions {
+            delimiter: Some(';'),
+            ..Default::default()
+        };
+        let (headers, rows) = table("name;note\nTea;hot, sweet\n", &options);
+        assert_eq!(headers, ["name", "note"]);
+        assert_eq!(rows, [["Tea", "hot, sweet"]]);
+
+        let options = CsvTableOptions {
+            delimiter: Some('→'),
+            ..Default::default()
+        };
+        let errors = table_from_csv(b"a,b", &options).unwrap_err();
+        assert_eq!(errors.errors[0].path, "delimiter");
+    }
+
+    #[test]
+    fn parses_alignments() {
+        let options = CsvTableOptions {
+            alignment: Some("left, Right,center".to_string()),
+            ..Default::default()
+        };
+        let component = table_from_csv(b"a,b,c\n1,2,3\n", &options).unwrap();
+        let BlogComponent::Table { alignment, .. } = component else {
+            panic!("not a table");
+        };
+        assert_eq!(
+            alignment,
+            [
+                ColumnAlignment::Left,
+                ColumnAlignment::Right,
+                ColumnAlignment::Center
+            ]
+        );
+
+        let options = CsvTableOptions {
+            alignment: Some("left,middle".to_string()),
+            ..Default::default()
+        };
+        let errors = table_from_csv(b"a,b\n", &options).unwrap_err();
+        assert_eq!(errors.errors[0].path, "alignment");
+        assert_eq!(errors.errors[0].message, "unknown alignment `middle`");
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let errors = table_from_csv(b"a,b,c\n1,2\n", &CsvTableOptions::default()).unwrap_err();
+        assert_eq!(errors.errors[0].path, "csv");
+    }
+
+    #[test]
+    fn rejects_a_missing_header_row() {
+        for data in ["", "\n", ",,\n1,2,3\n"] {
+            let errors = table_from_csv(data.as_bytes(), &CsvTableOptions::default()).unwrap_err();
+            assert_eq!(errors.errors[0].path, "csv", "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn tables_are_held_to_the_text_limits() {
+        let long_cell = "x".repeat(MAX_TEXT_LENGTH + 1);
+        let data = format!("a,b\n1,{}\n", long_cell);
+        let component = table_from_csv(data.as_bytes(), &CsvTableOptions::default()).unwrap();
+        let errors = validate_components(&[component]).unwrap_err();
+        assert!(errors
+            .errors
+            .iter()
+            .any(|issue| issue.path == "$.components[0].rows[0][1]"));
+
+        let cell = "x".repeat(MAX_TEXT_LENGTH / 4);
+        let data = format!("a,b\n{0},{0}\n{0},{0}\n{0},{0}\n", cell);
+        let component = table_from_csv(data.as_bytes(), &CsvTableOptions::default()).unwrap();
+        let errors = validate_components(&[component]).unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.components[0].rows");
+    }
+}
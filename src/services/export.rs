@@ -0,0 +1,732 @@
+//! Getting content out of the database: Markdown with front matter for a
+//! single post, and the site archive for backups and migrations.
+//!
+//! # Archive format
+//!
+//! A site archive is a single JSON document:
+//!
+//! ```json
+//! {
+//!   "format": "ruststack-archive",
+//!   "version": 1,
+//!   "exported_at": "2024-01-31T12:00:00Z",
+//!   "schema_version": 2,
+//!   "posts": [{ "id": "…", "title": "…", "slug": "…", "components": [], … }],
+//!   "featured": [{ "post_id": "…", "position": 1 }],
+//!   "slug_history": [{ "slug": "old-slug", "post_id": "…", "changed_at": "…" }],
+//!   "redirects": [{ "id": "…", "from_path": "/old", "to_path": "/new", … }],
+//!   "media": [{ "url": "/static/a.png", "kind": "image", "posts": ["slug"] }],
+//!   "taxonomy": {
+//!     "tags": [{ "name": "rust", "posts": 3 }],
+//!     "series": [{ "name": "Async", "posts": ["part-1", "part-2"] }]
+//!   }
+//! }
+//! ```
+//!
+//! `posts` holds every post, drafts included, exactly as the API returns
+//! them, with `components` in component schema `schema_version`. Importing
+//! validates them like posts sent to the admin API, keeping unreadable
+//! components as they are, and restores them by `id` with their
+//! timestamps. `featured` lists the posts featured on
+//! the homepage, `slug_history` the slugs posts used to have and
+//! `redirects` the admin-managed redirects; archives written before they
+//! existed import without them. `media` and `taxonomy` are derived from the
+//! posts for tools that only need an inventory; they are ignored on import.
+
+use crate::models::blog::{BlogComponent, BlogPost, ColumnAlignment, FeaturedEntry, OldSlug};
+use crate::models::component_schema::{upgrade_components, CURRENT_SCHEMA_VERSION};
+use crate::models::redirect::{Redirect, RedirectInput};
+use crate::services::markdown_import::FrontMatter;
+use crate::services::redirects::{normalize_redirect, validate_redirect};
+use crate::services::validation::{validate_stored_post, ValidationErrors, ValidationIssue};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+pub const ARCHIVE_FORMAT: &str = "ruststack-archive";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteArchive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Component schema version the posts' `components` are written in.
+    pub schema_version: i32,
+    pub posts: Vec<BlogPost>,
+    #[serde(default)]
+    pub featured: Vec<FeaturedEntry>,
+    #[serde(default)]
+    pub slug_history: Vec<OldSlug>,
+    #[serde(default)]
+    pub redirects: Vec<Redirect>,
+    #[serde(default)]
+    pub media: Vec<MediaEntry>,
+    #[serde(default)]
+    pub taxonomy: Taxonomy,
+}
+
+/// A file referenced by one or more posts.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaEntry {
+    pub url: String,
+    pub kind: MediaKind,
+    /// Slugs of the posts using it.
+    pub posts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Cover,
+    Image,
+    Video,
+    Poster,
+    Captions,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Taxonomy {
+    pub tags: Vec<TagCount>,
+    pub series: Vec<SeriesEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagCount {
+    pub name: String,
+    pub posts: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeriesEntry {
+    pub name: String,
+    /// Slugs in series order.
+    pub posts: Vec<String>,
+}
+
+pub fn build_archive(
+    posts: Vec<BlogPost>,
+    featured: Vec<FeaturedEntry>,
+    slug_history: Vec<OldSlug>,
+    redirects: Vec<Redirect>,
+) -> SiteArchive {
+    let mut media: BTreeMap<(String, MediaKind), Vec<String>> = BTreeMap::new();
+    let mut tags: BTreeMap<String, usize> = BTreeMap::new();
+    let mut series: BTreeMap<String, Vec<(Option<i32>, String)>> = BTreeMap::new();
+
+    for post in &posts {
+        let mut add_media = |url: &str, kind: MediaKind| {
+            let users = media.entry((url.to_string(), kind)).or_default();
+            if !users.contains(&post.slug) {
+                users.push(post.slug.clone());
+            }
+        };
+        if let Some(cover_image) = &post.cover_image {
+            add_media(cover_image, MediaKind::Cover);
+        }
+        for component in &post.components {
+            collect_media(component, &mut add_media);
+        }
+
+        for tag in &post.tags {
+            *tags.entry(tag.clone()).or_default() += 1;
+        }
+        if let Some(name) = &post.series {
+            series
+                .entry(name.clone())
+                .or_default()
+                .push((post.series_position, post.slug.clone()));
+        }
+    }
+
+    SiteArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        schema_version: CURRENT_SCHEMA_VERSION,
+        featured,
+        slug_history,
+        redirects,
+        media: media
+            .into_iter()
+            .map(|((url, kind), posts)| MediaEntry { url, kind, posts })
+            .collect(),
+        taxonomy: Taxonomy {
+            tags: tags
+                .into_iter()
+                .map(|(name, posts)| TagCount { name, posts })
+                .collect(),
+            series: series
+                .into_iter()
+                .map(|(name, mut entries)| {
+                    entries.sort_by_key(|(position, _)| position.unwrap_or(i32::MAX));
+                    SeriesEntry {
+                        name,
+                        posts: entries.into_iter().map(|(_, slug)| slug).collect(),
+                    }
+                })
+                .collect(),
+        },
+        posts,
+    }
+}
+
+fn collect_media(component: &BlogComponent, add: &mut impl FnMut(&str, MediaKind)) {
+    match component {
+        BlogComponent::Image { src, .. } => add(src, MediaKind::Image),
+        BlogComponent::Video {
+            src,
+            poster,
+            captions,
+        } => {
+            add(src, MediaKind::Video);
+            if let Some(poster) = poster {
+                add(poster, MediaKind::Poster);
+            }
+            for track in captions {
+                add(&track.src, MediaKind::Captions);
+            }
+        }
+        _ => {}
+    }
+    for child in component.children() {
+        collect_media(child, add);
+    }
+}
+
+/// Read an archive, upgrading its components to the current schema version
+/// and checking everything it would restore.
+pub fn parse_archive(mut value: Value) -> Result<SiteArchive, ValidationErrors> {
+    if value.get("format").and_then(Value::as_str) != Some(ARCHIVE_FORMAT) {
+        return Err(ValidationErrors::single(
+            "$.format",
+            format!("expected \"{}\"", ARCHIVE_FORMAT),
+        ));
+    }
+    match value.get("version").and_then(Value::as_u64) {
+        Some(version) if version == ARCHIVE_VERSION as u64 => {}
+        _ => {
+            return Err(ValidationErrors::single(
+                "$.version",
+                format!("only archive version {} is supported", ARCHIVE_VERSION),
+            ))
+        }
+    }
+
+    let schema_version = value
+        .get("schema_version")
+        .and_then(Value::as_i64)
+        .and_then(|version| i32::try_from(version).ok())
+        .ok_or_else(|| ValidationErrors::single("$.schema_version", "must be an integer"))?;
+
+    if let Some(posts) = value.get_mut("posts").and_then(Value::as_array_mut) {
+        for (index, post) in posts.iter_mut().enumerate() {
+            if let Some(components) = post.get_mut("components") {
+                upgrade_components(components, schema_version).map_err(|e| {
+                    ValidationErrors::single(
+                        &format!("$.posts[{}].components", index),
+                        e.to_string(),
+                    )
+                })?;
+            }
+        }
+    }
+
+    let mut archive: SiteArchive =
+        serde_json::from_value(value).map_err(|e| ValidationErrors::single("$", e.to_string()))?;
+    archive.schema_version = CURRENT_SCHEMA_VERSION;
+    for redirect in &mut archive.redirects {
+        let input = normalize_redirect(&RedirectInput {
+            from_path: redirect.from_path.clone(),
+            to_path: redirect.to_path.clone(),
+            permanent: redirect.permanent,
+        });
+        redirect.from_path = input.from_path;
+        redirect.to_path = input.to_path;
+    }
+
+    let errors = check_archive(&archive);
+    if !errors.is_empty() {
+        return Err(ValidationErrors { errors });
+    }
+    Ok(archive)
+}
+
+fn check_archive(archive: &SiteArchive) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut ids = HashSet::new();
+    let mut slugs = HashSet::new();
+    for (index, post) in archive.posts.iter().enumerate() {
+        let at = format!("$.posts[{}]", index);
+        if let Err(errors) = validate_stored_post(&post.to_input()) {
+            issues.extend(within(errors, &at));
+        }
+        if post.slug.is_empty() {
+            issues.push(issue(format!("{}.slug", at), "must not be empty"));
+        } else if !slugs.insert(post.slug.as_str()) {
+            issues.push(issue(
+                format!("{}.slug", at),
+                "is used by another post in the archive",
+            ));
+        }
+        if !ids.insert(post.id) {
+            issues.push(issue(
+                format!("{}.id", at),
+                "is used by another post in the archive",
+            ));
+        }
+    }
+
+    for (index, entry) in archive.featured.iter().enumerate() {
+        if !ids.contains(&entry.post_id) {
+            issues.push(issue(
+                format!("$.featured[{}].post_id", index),
+                "must be the id of a post in the archive",
+            ));
+        }
+    }
+    for (index, old_slug) in archive.slug_history.iter().enumerate() {
+        if !ids.contains(&old_slug.post_id) {
+            issues.push(issue(
+                format!("$.slug_history[{}].post_id", index),
+                "must be the id of a post in the archive",
+            ));
+        }
+    }
+
+    for (index, redirect) in archive.redirects.iter().enumerate() {
+        let input = RedirectInput {
+            from_path: redirect.from_path.clone(),
+            to_path: redirect.to_path.clone(),
+            permanent: redirect.permanent,
+        };
+        if let Err(errors) = validate_redirect(&input) {
+            issues.extend(within(errors, &format!("$.redirects[{}]", index)));
+        }
+    }
+
+    issues
+}
+
+fn issue(path: String, message: &str) -> ValidationIssue {
+    ValidationIssue {
+        component: None,
+        path,
+        message: message.to_string(),
+    }
+}
+
+/// Issues found in one item of the archive, with paths from its root.
+fn within(errors: ValidationErrors, prefix: &str) -> impl Iterator<Item = ValidationIssue> + '_ {
+    errors.errors.into_iter().map(move |issue| ValidationIssue {
+        path: issue.path.replacen('$', prefix, 1),
+        ..issue
+    })
+}
+
+/// Convert a post to Markdown with YAML front matter, in the form the
+/// Markdown importer reads back.
+///
+/// Components without a Markdown equivalent are written as the closest
+/// GitHub-flavoured Markdown or inline HTML, so tabs, steps and embeds come
+/// back as plain content when re-imported. Use the site archive for a
+/// lossless copy.
+pub fn post_to_markdown(post: &BlogPost) -> String {
+    let front_matter = FrontMatter {
+        title: Some(post.title.clone()),
+        slug: Some(post.slug.clone()),
+        date: post.published_at.map(|date| date.to_rfc3339()),
+        draft: post.published_at.is_none(),
+        tags: post.tags.clone(),
+        cover_image: post.cover_image.clone(),
+        series: post.series.clone(),
+        series_position: post.series_position,
+    };
+
+    let yaml = serde_yaml::to_string(&front_matter).unwrap_or_default();
+    format!(
+        "---\n{}---\n\n{}\n",
+        yaml,
+        components_to_markdown(&post.components)
+    )
+}
+
+fn components_to_markdown(components: &[BlogComponent]) -> String {
+    components
+        .iter()
+        .map(component_to_markdown)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn component_to_markdown(component: &BlogComponent) -> String {
+    match component {
+        BlogComponent::Heading { text } => format!("## {}", text),
+        BlogComponent::Paragraph { markdown } => markdown.trim().to_string(),
+        BlogComponent::Code { language, code } => fenced(language, code),
+        BlogComponent::Callout { style, markdown } => {
+            let kind = match style.as_str() {
+                "success" => "TIP",
+                "info" => "IMPORTANT",
+                "warning" => "WARNING",
+                "error" => "CAUTION",
+                _ => "NOTE",
+            };
+            quote(&format!("[!{}]\n{}", kind, markdown.trim()))
+        }
+        BlogComponent::Card {
+            title,
+            description,
+            link,
+        } => format!("**[{}]({})**\n\n{}", title, link, description),
+        BlogComponent::Image { src, alt, caption } => match caption {
+            Some(caption) => format!("![{}]({} \"{}\")", alt, src, caption.replace('"', "\\\"")),
+            None => format!("![{}]({})", alt, src),
+        },
+        BlogComponent::Quote { text, author } => match author {
+            Some(author) => quote(&format!("{}\n\n— {}", text, author)),
+            None => quote(text),
+        },
+        BlogComponent::Tabs { tabs } => tabs
+            .iter()
+            .map(|tab| {
+                format!(
+                    "**{}**\n\n{}",
+                    tab.label,
+                    components_to_markdown(&tab.components)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        BlogComponent::Details {
+            summary,
+            markdown,
+            components,
+            open,
+        } => {
+            let mut body = markdown.clone().unwrap_or_default();
+            if !components.is_empty() {
+                body = format!("{}\n\n{}", body, components_to_markdown(components));
+            }
+            format!(
+                "<details{}>\n<summary>{}</summary>\n\n{}\n\n</details>",
+                if *open { " open" } else { "" },
+                summary,
+                body.trim()
+            )
+        }
+        BlogComponent::Steps { steps } => steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let mut block = format!("**{}. {}**", index + 1, step.title);
+                if let Some(markdown) = &step.markdown {
+                    block = format!("{}\n\n{}", block, markdown.trim());
+                }
+                if !step.components.is_empty() {
+                    block = format!("{}\n\n{}", block, components_to_markdown(&step.components));
+                }
+                block
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        BlogComponent::Math { latex } => fenced("math", latex),
+        BlogComponent::Diagram { source, caption } => {
+            let block = fenced("dot", source);
+            match caption {
+                Some(caption) => format!("{}\n\n*{}*", block, caption),
+                None => block,
+            }
+        }
+        BlogComponent::Video { src, poster, .. } => match poster {
+            Some(poster) => format!(
+                r#"<video src="{}" poster="{}" controls></video>"#,
+                src, poster
+            ),
+            None => format!(r#"<video src="{}" controls></video>"#, src),
+        },
+        BlogComponent::Embed {
+            provider,
+            url,
+            title,
+        } => format!(
+            "[{}]({})",
+            title.as_deref().unwrap_or(provider.display_name()),
+            url
+        ),
+        BlogComponent::Table {
+            headers,
+            rows,
+            alignment,
+            caption,
+            ..
+        } => {
+            let row = |cells: &[String]| {
+                let cells: Vec<String> = cells.iter().map(|cell| table_cell(cell)).collect();
+                format!("| {} |", cells.join(" | "))
+            };
+            let separator: Vec<&str> = (0..headers.len())
+                .map(
+                    |column| match alignment.get(column).copied().unwrap_or_default() {
+                        ColumnAlignment::Left => "---",
+                        ColumnAlignment::Center => ":---:",
+                        ColumnAlignment::Right => "---:",
+                    },
+                )
+                .collect();
+
+            let mut lines = vec![row(headers), format!("| {} |", separator.join(" | "))];
+            lines.extend(rows.iter().map(|cells| row(cells)));
+            let table = lines.join("\n");
+            match caption {
+                Some(caption) => format!("{}\n\n*{}*", table, caption),
+                None => table,
+            }
+        }
+        BlogComponent::Unknown(unknown) => format!(
+            "<!-- unreadable component: {} -->",
+            unknown.raw.to_string().replace("--", "- -")
+        ),
+    }
+}
+
+/// A fenced code block whose fence is longer than any backtick run inside.
+fn fenced(info: &str, code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        info,
+        code.trim_end_matches('\n'),
+        fence
+    )
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn table_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::markdown_import::{post_from_markdown, MarkdownImportOptions};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    const FIRST: &str = "6b0e1b8e-3f0c-4a53-9d4c-0d3c2a1b7f01";
+    const SECOND: &str = "6b0e1b8e-3f0c-4a53-9d4c-0d3c2a1b7f02";
+
+    fn post(id: &str, slug: &str, components: Value) -> BlogPost {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": format!("Post {}", slug),
+            "slug": slug,
+            "published_at": "2025-06-07T08:09:10.123456Z",
+            "cover_image": "/static/cover.png",
+            "tags": ["rust"],
+            "series": "Fundamentals",
+            "series_position": 1,
+            "components": components,
+            "created_at": "2025-06-01T00:00:00Z",
+            "updated_at": "2025-06-07T08:09:10.123456Z",
+        }))
+        .unwrap()
+    }
+
+    fn archive() -> SiteArchive {
+        let posts = vec![
+            post(
+                FIRST,
+                "ownership",
+                json!([
+                    { "type": "heading", "text": "Moves" },
+                    { "type": "code", "language": "rust", "code": "let b = a;" },
+                ]),
+            ),
+            BlogPost {
+                published_at: None,
+                ..post(SECOND, "borrowing", json!([]))
+            },
+        ];
+        let featured = vec![FeaturedEntry {
+            post_id: posts[0].id,
+            position: Some(1),
+        }];
+        let slug_history = vec![OldSlug {
+            slug: "ownership-draft".to_string(),
+            post_id: posts[0].id,
+            changed_at: posts[0].updated_at,
+        }];
+        let redirects = vec![Redirect {
+            id: Uuid::nil(),
+            from_path: "/old".to_string(),
+            to_path: "/blog/ownership".to_string(),
+            permanent: true,
+            created_at: posts[0].created_at,
+            updated_at: posts[0].updated_at,
+        }];
+        build_archive(posts, featured, slug_history, redirects)
+    }
+
+    fn paths(errors: ValidationErrors) -> Vec<String> {
+        errors.errors.into_iter().map(|issue| issue.path).collect()
+    }
+
+    #[test]
+    fn archives_read_back_as_exported() {
+        let exported = serde_json::to_value(archive()).unwrap();
+        let restored = parse_archive(exported.clone()).unwrap();
+        assert_eq!(restored.posts.len(), 2);
+        assert_eq!(restored.featured[0].position, Some(1));
+        assert_eq!(restored.slug_history[0].slug, "ownership-draft");
+        assert_eq!(restored.redirects[0].to_path, "/blog/ownership");
+        assert_eq!(serde_json::to_value(restored).unwrap(), exported);
+    }
+
+    #[test]
+    fn archives_without_features_history_or_redirects_still_import() {
+        let mut exported = serde_json::to_value(archive()).unwrap();
+        for key in ["featured", "slug_history", "redirects"] {
+            exported.as_object_mut().unwrap().remove(key);
+        }
+        let restored = parse_archive(exported).unwrap();
+        assert_eq!(restored.posts.len(), 2);
+        assert!(restored.featured.is_empty());
+        assert!(restored.slug_history.is_empty());
+        assert!(restored.redirects.is_empty());
+    }
+
+    #[test]
+    fn posts_are_validated_before_they_are_restored() {
+        let mut exported = serde_json::to_value(archive()).unwrap();
+        exported["posts"][0]["components"][1]["code"] = json!("");
+        exported["posts"][1]["title"] = json!(" ");
+        exported["posts"][1]["slug"] = json!("ownership");
+
+        assert_eq!(
+            paths(parse_archive(exported).unwrap_err()),
+            [
+                "$.posts[0].components[1].code",
+                "$.posts[1].title",
+                "$.posts[1].slug",
+            ]
+        );
+    }
+
+    #[test]
+    fn unreadable_components_are_restored_as_they_are() {
+        let mut exported = serde_json::to_value(archive()).unwrap();
+        let sparkle = json!({ "type": "sparkle", "colour": "gold" });
+        exported["posts"][0]["components"][0] = sparkle.clone();
+
+        let restored = parse_archive(exported).unwrap();
+        assert!(matches!(
+            restored.posts[0].components[0],
+            BlogComponent::Unknown(_)
+        ));
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap()["posts"][0]["components"][0],
+            sparkle
+        );
+    }
+
+    #[test]
+    fn features_and_old_slugs_must_belong_to_archived_posts() {
+        let mut exported = serde_json::to_value(archive()).unwrap();
+        exported["posts"][1]["id"] = json!(FIRST);
+        exported["featured"][0]["post_id"] = json!(Uuid::nil());
+        exported["slug_history"][0]["post_id"] = json!(SECOND);
+
+        assert_eq!(
+            paths(parse_archive(exported).unwrap_err()),
+            [
+                "$.posts[1].id",
+                "$.featured[0].post_id",
+                "$.slug_history[0].post_id",
+            ]
+        );
+    }
+
+    #[test]
+    fn redirects_are_normalized_and_checked() {
+        let mut exported = serde_json::to_value(archive()).unwrap();
+        exported["redirects"][0]["from_path"] = json!(" /old/ ");
+        let restored = parse_archive(exported.clone()).unwrap();
+        assert_eq!(restored.redirects[0].from_path, "/old");
+
+        exported["redirects"][0]["to_path"] = json!("//evil.example");
+        assert_eq!(
+            paths(parse_archive(exported).unwrap_err()),
+            ["$.redirects[0].to_path"]
+        );
+    }
+
+    #[test]
+    fn markdown_exports_import_back() {
+        let post = &archive().posts[0];
+        let markdown = post_to_markdown(post);
+        let imported = post_from_markdown(&markdown, &MarkdownImportOptions::default()).unwrap();
+
+        assert_eq!(imported.title, post.title);
+        assert_eq!(imported.slug, post.slug);
+        assert_eq!(imported.published_at, post.published_at);
+        assert_eq!(imported.cover_image, post.cover_image);
+        assert_eq!(imported.tags, post.tags);
+        assert_eq!(imported.series, post.series);
+        assert_eq!(imported.series_position, post.series_position);
+        assert_eq!(
+            serde_json::to_value(&imported.components).unwrap(),
+            serde_json::to_value(&post.components).unwrap()
+        );
+    }
+
+    #[test]
+    fn drafts_export_as_drafts() {
+        let post = &archive().posts[1];
+        let markdown = post_to_markdown(post);
+        assert!(markdown.contains("draft: true"), "{}", markdown);
+        let imported = post_from_markdown(&markdown, &MarkdownImportOptions::default()).unwrap();
+        assert_eq!(imported.published_at, None);
+    }
+
+    #[test]
+    fn fences_outlast_the_backticks_inside() {
+        let markdown = component_to_markdown(&BlogComponent::Code {
+            language: "md".to_string(),
+            code: "```rust\nfn main() {}\n```\n".to_string(),
+        });
+        assert_eq!(markdown, "````md\n```rust\nfn main() {}\n```\n````");
+    }
+
+    #[test]
+    fn table_cells_keep_their_pipes() {
+        let table: BlogComponent = serde_json::from_value(json!({
+            "type": "table",
+            "headers": ["Operator", "Meaning"],
+            "rows": [["a | b", "or"]],
+            "alignment": ["left", "right"],
+        }))
+        .unwrap();
+        assert_eq!(
+            component_to_markdown(&table),
+            "| Operator | Meaning |\n| --- | ---: |\n| a \\| b | or |"
+        );
+    }
+}
@@ -0,0 +1,464 @@
+use crate::models::blog::{BlogComponent, BlogPostInput};
+use crate::services::slugs::to_slug;
+use crate::services::validation::{validate_post, ValidationErrors};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use pulldown_cmark::{
+    BlockQuoteKind, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Options for importing a Markdown article, taken from the query string of
+/// the import request or the command line.
+#[derive(Debug, Default, Deserialize)]
+pub struct MarkdownImportOptions {
+    /// Slug to use when the front matter doesn't set one, such as the file
+    /// name; otherwise it is generated from the title when the post is saved.
+    pub slug: Option<String>,
+    /// Convert the article without saving it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Front matter understood by the importer and written by the Markdown
+/// exporter. Unknown keys are ignored so articles written for other static
+/// site generators import as-is.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrontMatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    /// Publication date; posts without one are imported as drafts.
+    #[serde(alias = "published_at", skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub draft: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(
+        alias = "cover",
+        alias = "image",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cover_image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series_position: Option<i32>,
+}
+
+type Block<'a> = [(Event<'a>, Range<usize>)];
+
+/// Convert a Markdown article with optional YAML front matter into a post.
+///
+/// Headings, fenced code, GitHub-style alerts (`> [!WARNING]`) and images
+/// standing on their own line become their own components; everything in
+/// between is kept as Markdown in `paragraph` components. A leading `# Title`
+/// is not kept, since the page renders the title itself; it becomes the title
+/// when the front matter has none.
+pub fn post_from_markdown(
+    source: &str,
+    options: &MarkdownImportOptions,
+) -> Result<BlogPostInput, ValidationErrors> {
+    let events: Vec<(Event, Range<usize>)> = Parser::new_ext(source, parser_options())
+        .into_offset_iter()
+        .collect();
+
+    let mut front_matter = FrontMatter::default();
+    let mut heading_title = None;
+    let mut components = Vec::new();
+    let mut markdown = Vec::new();
+
+    for block in top_level_blocks(&events) {
+        let (first, range) = &block[0];
+        match first {
+            Event::Start(Tag::MetadataBlock(_)) => {
+                let yaml = text_of(block);
+                if !yaml.trim().is_empty() {
+                    front_matter = serde_yaml::from_str(&yaml)
+                        .map_err(|e| ValidationErrors::single("$.front_matter", e.to_string()))?;
+                }
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                let text = text_of(block).trim().to_string();
+                let is_leading = components.is_empty() && markdown.is_empty();
+                if *level == HeadingLevel::H1 && is_leading && heading_title.is_none() {
+                    heading_title = Some(text);
+                    continue;
+                }
+                flush_markdown(&mut markdown, &mut components);
+                components.push(BlogComponent::Heading { text });
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next(),
+                    CodeBlockKind::Indented => None,
+                };
+                flush_markdown(&mut markdown, &mut components);
+                components.push(BlogComponent::Code {
+                    language: language.unwrap_or("text").to_string(),
+                    code: text_of(block).trim_end_matches('\n').to_string(),
+                });
+            }
+            Event::Start(Tag::BlockQuote(Some(kind))) => {
+                let inner = &block[1..block.len() - 1];
+                let body = match (inner.first(), inner.iter().map(|(_, r)| r.end).max()) {
+                    (Some((_, first)), Some(end)) => unquote(&source[first.start..end]),
+                    _ => String::new(),
+                };
+                flush_markdown(&mut markdown, &mut components);
+                components.push(BlogComponent::Callout {
+                    style: callout_style(*kind).to_string(),
+                    markdown: body,
+                });
+            }
+            Event::Start(Tag::Paragraph) if lone_image(block).is_some() => {
+                flush_markdown(&mut markdown, &mut components);
+                components.extend(lone_image(block));
+            }
+            _ => markdown.push(source[range.clone()].trim_end().to_string()),
+        }
+    }
+    flush_markdown(&mut markdown, &mut components);
+
+    let published_at = match (&front_matter.date, front_matter.draft) {
+        (Some(date), false) => Some(parse_date(date).ok_or_else(|| {
+            ValidationErrors::single(
+                "$.front_matter.date",
+                "expected a date like 2024-01-31 or an RFC 3339 timestamp",
+            )
+        })?),
+        _ => None,
+    };
+
+    let title = front_matter.title.or(heading_title).unwrap_or_default();
+    let slug = front_matter
+        .slug
+        .or_else(|| options.slug.as_deref().map(to_slug))
+        .unwrap_or_default();
+
+    let input = BlogPostInput {
+        title,
+        slug,
+        published_at,
+        cover_image: front_matter.cover_image,
+        tags: front_matter.tags,
+        series: front_matter.series,
+        series_position: front_matter.series_position,
+        components,
+    };
+    validate_post(&input)?;
+    Ok(input)
+}
+
+fn parser_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_GFM);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options
+}
+
+/// Split the event stream into its top-level blocks, each running from its
+/// `Start` event to the matching `End`.
+fn top_level_blocks<'e, 'a>(events: &'e Block<'a>) -> Vec<&'e Block<'a>> {
+    let mut blocks = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, (event, _)) in events.iter().enumerate() {
+        match event {
+            Event::Start(_) => {
+                if depth == 0 {
+                    start = index;
+                }
+                depth += 1;
+            }
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    blocks.push(&events[start..=index]);
+                }
+            }
+            _ if depth == 0 => blocks.push(&events[index..=index]),
+            _ => {}
+        }
+    }
+    blocks
+}
+
+fn text_of(block: &Block) -> String {
+    block
+        .iter()
+        .filter_map(|(event, _)| match event {
+            Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+            Event::SoftBreak | Event::HardBreak => Some(" "),
+            _ => None,
+        })
+        .collect()
+}
+
+/// An `image` component for a paragraph holding nothing but one image.
+fn lone_image(block: &Block) -> Option<BlogComponent> {
+    let Event::Start(Tag::Image {
+        dest_url, title, ..
+    }) = &block.get(1)?.0
+    else {
+        return None;
+    };
+
+    let end = block
+        .iter()
+        .position(|(event, _)| matches!(event, Event::End(TagEnd::Image)))?;
+    if end != block.len() - 2 {
+        return None;
+    }
+
+    Some(BlogComponent::Image {
+        src: dest_url.to_string(),
+        alt: text_of(&block[2..end]),
+        caption: Some(title.to_string()).filter(|title| !title.is_empty()),
+    })
+}
+
+fn callout_style(kind: BlockQuoteKind) -> &'static str {
+    match kind {
+        BlockQuoteKind::Note => "note",
+        BlockQuoteKind::Tip => "success",
+        BlockQuoteKind::Important => "info",
+        BlockQuoteKind::Warning => "warning",
+        BlockQuoteKind::Caution => "error",
+    }
+}
+
+/// Strip one level of `>` quoting from the source of a blockquote's body.
+fn unquote(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix('>') {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                None => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn flush_markdown(markdown: &mut Vec<String>, components: &mut Vec<BlogComponent>) {
+    if !markdown.is_empty() {
+        components.push(BlogComponent::Paragraph {
+            markdown: markdown.join("\n\n"),
+        });
+        markdown.clear();
+    }
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|datetime| datetime.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn import(source: &str) -> Result<BlogPostInput, ValidationErrors> {
+        post_from_markdown(source, &MarkdownImportOptions::default())
+    }
+
+    fn components(post: &BlogPostInput) -> Value {
+        serde_json::to_value(&post.components).unwrap()
+    }
+
+    #[test]
+    fn reads_front_matter() {
+        let post = import(
+            "---
+title: Borrowing
+slug: borrowing-explained
+date: 2025-06-07
+tags: [rust, ownership]
+cover: /static/cover.png
+series: Rust Fundamentals
+series_position: 2
+layout: post
+---
+
+Body text.
+",
+        )
+        .unwrap();
+        assert_eq!(post.title, "Borrowing");
+        assert_eq!(post.slug, "borrowing-explained");
+        assert_eq!(
+            post.published_at,
+            NaiveDate::from_ymd_opt(2025, 6, 7)
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|datetime| datetime.and_utc())
+        );
+        assert_eq!(post.tags, ["rust", "ownership"]);
+        assert_eq!(post.cover_image.as_deref(), Some("/static/cover.png"));
+        assert_eq!(post.series.as_deref(), Some("Rust Fundamentals"));
+        assert_eq!(post.series_position, Some(2));
+        assert_eq!(
+            components(&post),
+            json!([{ "type": "paragraph", "markdown": "Body text." }])
+        );
+    }
+
+    #[test]
+    fn drafts_and_undated_posts_are_unpublished() {
+        let post = import("---\ntitle: Draft\ndate: 2025-06-07\ndraft: true\n---\nText\n").unwrap();
+        assert_eq!(post.published_at, None);
+        let post = import("---\ntitle: Undated\n---\nText\n").unwrap();
+        assert_eq!(post.published_at, None);
+    }
+
+    #[test]
+    fn works_without_front_matter() {
+        let options = MarkdownImportOptions {
+            slug: Some("My File".to_string()),
+            ..Default::default()
+        };
+        let post = post_from_markdown("# From the heading\n\nText\n", &options).unwrap();
+        assert_eq!(post.title, "From the heading");
+        assert_eq!(post.slug, "my-file");
+        assert_eq!(post.published_at, None);
+    }
+
+    #[test]
+    fn rejects_malformed_front_matter() {
+        let errors = import("---\ntitle: [unclosed\n---\nText\n").unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.front_matter");
+
+        let errors = import("---\ntitle: Dated\ndate: last tuesday\n---\nText\n").unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.front_matter.date");
+    }
+
+    #[test]
+    fn a_leading_heading_becomes_the_title() {
+        let post = import("# Lifetimes\n\nIntro\n\n# Second\n").unwrap();
+        assert_eq!(post.title, "Lifetimes");
+        assert_eq!(
+            components(&post),
+            json!([
+                { "type": "paragraph", "markdown": "Intro" },
+                { "type": "heading", "text": "Second" },
+            ])
+        );
+
+        // The front matter title wins, and the heading is still dropped.
+        let post = import("---\ntitle: Front\n---\n# Lifetimes\n\nIntro\n").unwrap();
+        assert_eq!(post.title, "Front");
+        assert_eq!(
+            components(&post),
+            json!([{ "type": "paragraph", "markdown": "Intro" }])
+        );
+    }
+
+    #[test]
+    fn splits_headings_code_and_markdown() {
+        let post = import(
+            "# Title
+
+One *two*
+
+- three
+
+## Section
+
+```rust ignore
+fn main() {}
+```
+
+    indented
+",
+        )
+        .unwrap();
+        assert_eq!(
+            components(&post),
+            json!([
+                { "type": "paragraph", "markdown": "One *two*\n\n- three" },
+                { "type": "heading", "text": "Section" },
+                { "type": "code", "language": "rust", "code": "fn main() {}" },
+                { "type": "code", "language": "text", "code": "indented" },
+            ])
+        );
+    }
+
+    #[test]
+    fn alerts_become_callouts() {
+        let post = import(
+            "# Title
+
+> [!WARNING]
+> Mind the **borrow**.
+> Twice.
+
+> [!TIP]
+> Use clippy.
+
+> Just a quote.
+",
+        )
+        .unwrap();
+        assert_eq!(
+            components(&post),
+            json!([
+                { "type": "callout", "style": "warning", "markdown": "Mind the **borrow**.\nTwice." },
+                { "type": "callout", "style": "success", "markdown": "Use clippy." },
+                { "type": "paragraph", "markdown": "> Just a quote." },
+            ])
+        );
+    }
+
+    #[test]
+    fn lone_images_become_image_components() {
+        let post = import(
+            "# Title
+
+![A crab](/static/crab.png \"Ferris\")
+
+Text with ![an inline image](/static/inline.png) in it.
+",
+        )
+        .unwrap();
+        assert_eq!(
+            components(&post),
+            json!([
+                { "type": "image", "src": "/static/crab.png", "alt": "A crab", "caption": "Ferris" },
+                { "type": "paragraph", "markdown": "Text with ![an inline image](/static/inline.png) in it." },
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_posts_that_fail_validation() {
+        let errors = import("Text without a title\n").unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.title");
+
+        let errors = import("# Title\n\n![Remote](javascript:alert(1))\n").unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.components[0].src");
+        assert_eq!(errors.errors[0].component, Some(0));
+    }
+}
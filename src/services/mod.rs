@@ -1 +1,11 @@
 pub mod blog;
+pub mod content_sync;
+pub mod csv_import;
+pub mod export;
+pub mod markdown_import;
+pub mod redirects;
+pub mod related;
+pub mod seed;
+pub mod slugs;
+pub mod users;
+pub mod validation;
@@ -0,0 +1,177 @@
+//! Admin-managed redirects from paths the site no longer serves. They are
+//! consulted only when nothing else answers a request, so a redirect can
+//! never shadow a live page.
+
+use crate::database::Database;
+use crate::models::redirect::{Redirect, RedirectInput};
+use crate::services::validation::{is_local_path, ValidationErrors, ValidationIssue};
+use anyhow::Result;
+use uuid::Uuid;
+
+/// Longest path or URL accepted on either side of a redirect.
+pub const MAX_REDIRECT_LENGTH: usize = 2048;
+
+pub struct RedirectService {
+    db: Database,
+}
+
+impl RedirectService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// The redirect for a request to `path`, ignoring a trailing slash.
+    pub async fn find(&self, path: &str) -> Result<Option<Redirect>> {
+        self.db.get_redirect_by_path(&normalize_path(path)).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<Redirect>> {
+        self.db.list_redirects().await
+    }
+
+    /// Validate and store a redirect. Invalid input and a path that already
+    /// redirects are returned as [`ValidationErrors`] inside the
+    /// `anyhow::Error`.
+    pub async fn create(&self, input: &RedirectInput) -> Result<Redirect> {
+        let input = normalize_redirect(input);
+        validate_redirect(&input)?;
+        if let Some(existing) = self.db.get_redirect_by_path(&input.from_path).await? {
+            return Err(ValidationErrors::single(
+                "$.from_path",
+                format!("already redirects to {}", existing.to_path),
+            )
+            .into());
+        }
+
+        self.db.insert_redirect(&input).await
+    }
+
+    /// Delete a redirect, returning whether there was one.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        self.db.delete_redirect(id).await
+    }
+}
+
+/// Trim both sides of a redirect and store them as they are matched.
+pub fn normalize_redirect(input: &RedirectInput) -> RedirectInput {
+    RedirectInput {
+        from_path: normalize_path(input.from_path.trim()),
+        to_path: normalize_target(input.to_path.trim()),
+        permanent: input.permanent,
+    }
+}
+
+/// Checks on a normalized redirect that don't need the database.
+pub fn validate_redirect(input: &RedirectInput) -> Result<(), ValidationErrors> {
+    let mut errors = Vec::new();
+    if let Err(message) = check_from_path(&input.from_path) {
+        errors.push(issue("$.from_path", message));
+    }
+    if let Err(message) = check_to_path(&input.to_path) {
+        errors.push(issue("$.to_path", message));
+    } else if input.to_path == input.from_path {
+        errors.push(issue("$.to_path", "must differ from `from_path`"));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors { errors })
+    }
+}
+
+/// Paths are stored and matched without a trailing slash.
+fn normalize_path(path: &str) -> String {
+    match path.trim_end_matches('/') {
+        "" if path.starts_with('/') => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Targets on this site are stored like the paths they redirect from;
+/// URLs as given.
+fn normalize_target(target: &str) -> String {
+    if target.starts_with('/') {
+        normalize_path(target)
+    } else {
+        target.to_string()
+    }
+}
+
+fn check_from_path(path: &str) -> Result<(), String> {
+    if !is_local_path(path) {
+        return Err("must be a path starting with `/`".to_string());
+    }
+    if path.contains(['?', '#']) || path.chars().any(char::is_whitespace) {
+        return Err("must be a bare path, without a query, fragment or spaces".to_string());
+    }
+    check_length(path)
+}
+
+/// A path on this site or an absolute `http(s)` URL.
+fn check_to_path(path: &str) -> Result<(), String> {
+    let is_path = is_local_path(path);
+    let is_url = url::Url::parse(path)
+        .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+    if !is_path && !is_url {
+        return Err("must be a path starting with `/` or an http(s) URL".to_string());
+    }
+    if path.chars().any(char::is_whitespace) {
+        return Err("must not contain spaces".to_string());
+    }
+    check_length(path)
+}
+
+fn check_length(path: &str) -> Result<(), String> {
+    if path.len() > MAX_REDIRECT_LENGTH {
+        return Err(format!(
+            "must be at most {} bytes long",
+            MAX_REDIRECT_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn issue(path: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        component: None,
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_stay_on_this_site_or_go_to_web_urls() {
+        assert!(check_to_path("/blog/new-slug").is_ok());
+        assert!(check_to_path("https://example.com/post").is_ok());
+
+        for target in [
+            "//evil.example",
+            "/\\evil.example",
+            "/\\/evil.example",
+            "\\\\evil.example",
+            "javascript:alert(1)",
+            "blog/post",
+        ] {
+            assert!(check_to_path(target).is_err(), "{}", target);
+        }
+    }
+
+    #[test]
+    fn paths_with_backslashes_are_rejected() {
+        assert!(check_from_path("/old\\post").is_err());
+    }
+
+    #[test]
+    fn targets_on_this_site_lose_their_trailing_slash() {
+        assert_eq!(normalize_target("/blog/post/"), "/blog/post");
+        assert_eq!(normalize_target("/"), "/");
+        assert_eq!(
+            normalize_target("https://example.com/"),
+            "https://example.com/"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::models::blog::{components_text, RelatedCandidate};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+const TAG_WEIGHT: f64 = 3.0;
+const SERIES_WEIGHT: f64 = 2.0;
+const TEXT_WEIGHT: f64 = 1.0;
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "can", "her", "was", "one", "our",
+    "out", "has", "have", "had", "him", "his", "how", "its", "let", "may", "who", "did", "get",
+    "use", "that", "this", "with", "from", "they", "them", "then", "than", "there", "their",
+    "what", "when", "where", "which", "will", "would", "your", "into", "also", "just", "more",
+    "some", "such", "only", "other", "these", "those", "about", "been", "being", "were", "here",
+];
+
+/// Rank `candidates` by similarity to the post with `target_id`.
+///
+/// The score combines the Jaccard overlap of tags, membership of the same
+/// series and the cosine similarity of TF-IDF vectors built from the text
+/// of each post's components. Posts with a zero score are never returned.
+pub fn rank_related(target_id: Uuid, candidates: &[RelatedCandidate], limit: usize) -> Vec<Uuid> {
+    let Some(target_index) = candidates.iter().position(|c| c.id == target_id) else {
+        return Vec::new();
+    };
+    let target = &candidates[target_index];
+
+    let documents: Vec<HashMap<String, f64>> = candidates
+        .iter()
+        .map(|c| term_frequencies(&components_text(&c.components)))
+        .collect();
+
+    let idf = inverse_document_frequencies(&documents);
+    let vectors: Vec<HashMap<&str, f64>> = documents
+        .iter()
+        .map(|doc| {
+            doc.iter()
+                .map(|(term, tf)| (term.as_str(), tf * idf.get(term.as_str()).unwrap_or(&0.0)))
+                .collect()
+        })
+        .collect();
+
+    let target_tags: HashSet<&str> = target.tags.iter().map(String::as_str).collect();
+
+    let mut scored: Vec<(Uuid, f64)> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.id != target_id)
+        .map(|(index, candidate)| {
+            let tags: HashSet<&str> = candidate.tags.iter().map(String::as_str).collect();
+            let union = target_tags.union(&tags).count();
+            let tag_score = if union == 0 {
+                0.0
+            } else {
+                target_tags.intersection(&tags).count() as f64 / union as f64
+            };
+
+            let series_score = match (&target.series, &candidate.series) {
+                (Some(a), Some(b)) if a == b => 1.0,
+                _ => 0.0,
+            };
+
+            let text_score = cosine(&vectors[target_index], &vectors[index]);
+
+            let score =
+                TAG_WEIGHT * tag_score + SERIES_WEIGHT * series_score + TEXT_WEIGHT * text_score;
+            (candidate.id, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(limit).map(|(id, _)| id).collect()
+}
+
+fn term_frequencies(text: &str) -> HashMap<String, f64> {
+    let mut counts: HashMap<String, f64> = HashMap::new();
+    let mut total = 0.0;
+
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+    {
+        *counts.entry(word).or_default() += 1.0;
+        total += 1.0;
+    }
+
+    if total > 0.0 {
+        for count in counts.values_mut() {
+            *count /= total;
+        }
+    }
+
+    counts
+}
+
+fn inverse_document_frequencies(documents: &[HashMap<String, f64>]) -> HashMap<&str, f64> {
+    let mut document_counts: HashMap<&str, f64> = HashMap::new();
+    for doc in documents {
+        for term in doc.keys() {
+            *document_counts.entry(term.as_str()).or_default() += 1.0;
+        }
+    }
+
+    let total = documents.len() as f64;
+    document_counts
+        .into_iter()
+        .map(|(term, count)| (term, (total / count).ln()))
+        .collect()
+}
+
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+        .sum();
+    let norm_a = a.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norm_b = b.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::blog::BlogComponent;
+
+    fn candidate(tags: &[&str], series: Option<&str>, text: &str) -> RelatedCandidate {
+        RelatedCandidate {
+            id: Uuid::new_v4(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            series: series.map(str::to_string),
+            components: vec![BlogComponent::Paragraph {
+                markdown: text.to_string(),
+            }],
+        }
+    }
+
+    fn ids(candidates: &[&RelatedCandidate]) -> Vec<Uuid> {
+        candidates.iter().map(|candidate| candidate.id).collect()
+    }
+
+    #[test]
+    fn ranks_by_shared_tags() {
+        let target = candidate(&["rust", "async"], None, "delta");
+        let same_tags = candidate(&["async", "rust"], None, "alpha");
+        let one_tag = candidate(&["rust", "web"], None, "beta");
+        let untagged = candidate(&[], None, "gamma");
+        let candidates = [
+            untagged.clone(),
+            one_tag.clone(),
+            target.clone(),
+            same_tags.clone(),
+        ];
+
+        assert_eq!(
+            rank_related(target.id, &candidates, 5),
+            ids(&[&same_tags, &one_tag])
+        );
+    }
+
+    #[test]
+    fn ranks_the_same_series_above_a_shared_tag() {
+        let target = candidate(&["rust", "async", "web"], Some("Rust 101"), "delta");
+        let one_tag = candidate(&["rust"], None, "alpha");
+        let same_series = candidate(&[], Some("Rust 101"), "beta");
+        let other_series = candidate(&[], Some("Cooking"), "gamma");
+        let candidates = [
+            one_tag.clone(),
+            other_series,
+            same_series.clone(),
+            target.clone(),
+        ];
+
+        assert_eq!(
+            rank_related(target.id, &candidates, 5),
+            ids(&[&same_series, &one_tag])
+        );
+        assert_eq!(
+            rank_related(target.id, &candidates, 1),
+            ids(&[&same_series])
+        );
+    }
+
+    #[test]
+    fn ranks_by_text_similarity() {
+        let target = candidate(
+            &[],
+            None,
+            "The borrow checker enforces lifetimes and ownership",
+        );
+        let close = candidate(&[], None, "Lifetimes help the borrow checker in my garden");
+        let distant = candidate(&[], None, "Borrow tomatoes from the garden soil");
+        let unrelated = candidate(&[], None, "Pasta sauce with basil");
+        let candidates = [unrelated, distant.clone(), close.clone(), target.clone()];
+
+        assert_eq!(
+            rank_related(target.id, &candidates, 5),
+            ids(&[&close, &distant])
+        );
+    }
+
+    #[test]
+    fn ranks_nothing_for_an_unknown_post() {
+        let candidates = [candidate(&["rust"], None, "alpha")];
+        assert!(rank_related(Uuid::new_v4(), &candidates, 5).is_empty());
+    }
+}
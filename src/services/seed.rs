@@ -0,0 +1,213 @@
+//! Fixture sets for development and test databases, loaded with
+//! `ruststack-admin seed <set>`. Schema migrations never insert content.
+//!
+//! Posts are keyed by slug: seeding a set again creates only the posts that
+//! are missing and never overwrites one edited since.
+
+use crate::models::blog::{BlogComponent, BlogPostInput};
+use crate::services::blog::BlogService;
+use crate::services::validation::parse_post_input;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Posts generated by the load-test set unless told otherwise.
+pub const DEFAULT_LOAD_TEST_POSTS: usize = 5_000;
+
+/// Posts of the demo set, in the admin API's post format.
+const DEMO_POSTS: &[(&str, &str)] = &[
+    (
+        "understanding-lifetimes.json",
+        include_str!("../../fixtures/demo/understanding-lifetimes.json"),
+    ),
+    (
+        "ownership-and-borrowing.json",
+        include_str!("../../fixtures/demo/ownership-and-borrowing.json"),
+    ),
+];
+
+/// Posts of the e2e set: every component type, a draft and a series, with
+/// fixed dates so tests can rely on their order.
+const E2E_POSTS: &[(&str, &str)] = &[
+    (
+        "e2e-all-components.json",
+        include_str!("../../fixtures/e2e/e2e-all-components.json"),
+    ),
+    (
+        "e2e-draft.json",
+        include_str!("../../fixtures/e2e/e2e-draft.json"),
+    ),
+    (
+        "e2e-series-part-1.json",
+        include_str!("../../fixtures/e2e/e2e-series-part-1.json"),
+    ),
+    (
+        "e2e-series-part-2.json",
+        include_str!("../../fixtures/e2e/e2e-series-part-2.json"),
+    ),
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum FixtureSet {
+    /// A couple of hand-written posts for local development.
+    Demo,
+    /// `posts` generated posts, spread over the past years, for exercising
+    /// listings, pagination and related posts at scale.
+    LoadTest { posts: usize },
+    /// Posts end-to-end tests rely on.
+    E2e,
+}
+
+impl FixtureSet {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FixtureSet::Demo => "demo",
+            FixtureSet::LoadTest { .. } => "load-test",
+            FixtureSet::E2e => "e2e",
+        }
+    }
+
+    pub fn posts(&self) -> Result<Vec<BlogPostInput>> {
+        match self {
+            FixtureSet::Demo => parse_fixtures(DEMO_POSTS),
+            FixtureSet::LoadTest { posts } => Ok((1..=*posts).map(load_test_post).collect()),
+            FixtureSet::E2e => parse_fixtures(E2E_POSTS),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeedReport {
+    pub set: &'static str,
+    /// Posts created.
+    pub created: usize,
+    /// Posts whose slug already existed.
+    pub skipped: usize,
+}
+
+pub async fn seed(blog_service: &BlogService, set: FixtureSet) -> Result<SeedReport> {
+    let posts = set.posts()?;
+    let created = blog_service.create_missing_posts(&posts).await?;
+
+    Ok(SeedReport {
+        set: set.name(),
+        created,
+        skipped: posts.len() - created,
+    })
+}
+
+fn parse_fixtures(fixtures: &[(&str, &str)]) -> Result<Vec<BlogPostInput>> {
+    fixtures
+        .iter()
+        .map(|(name, source)| {
+            let value = serde_json::from_str(source)
+                .with_context(|| format!("Invalid fixture {}", name))?;
+            parse_post_input(value).with_context(|| format!("Invalid fixture {}", name))
+        })
+        .collect()
+}
+
+const TOPICS: &[&str] = &[
+    "Lifetimes",
+    "Traits",
+    "Async",
+    "Error Handling",
+    "Macros",
+    "Iterators",
+    "Smart Pointers",
+    "Concurrency",
+    "Testing",
+    "Unsafe",
+];
+
+const TAGS: &[&str] = &[
+    "rust",
+    "async",
+    "tokio",
+    "actix",
+    "sqlx",
+    "performance",
+    "testing",
+    "patterns",
+    "web",
+    "cli",
+    "traits",
+    "memory",
+];
+
+const SENTENCES: &[&str] = &[
+    "The borrow checker rejects this before it ever runs.",
+    "Ownership moves with the value, so the original binding can no longer be used.",
+    "Traits describe behaviour that many types can share.",
+    "An iterator adapter does nothing until something consumes it.",
+    "Errors are values, and `?` passes them up to the caller.",
+    "Futures do nothing unless they are polled by an executor.",
+    "A `Box` puts its contents on the heap and owns them.",
+    "Sharing state between threads needs `Arc`, and mutating it needs a lock.",
+];
+
+/// The `n`th generated post. Everything derives from `n`, so generating the
+/// same set twice gives the same posts.
+fn load_test_post(n: usize) -> BlogPostInput {
+    let topic = TOPICS[n % TOPICS.len()];
+    let tags = (0..=n % 3)
+        .map(|i| TAGS[(n * 7 + i * 5) % TAGS.len()].to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let paragraph = |offset: usize| {
+        (0..4)
+            .map(|i| SENTENCES[(n + offset + i * 3) % SENTENCES.len()])
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut components = vec![
+        BlogComponent::Paragraph {
+            markdown: paragraph(0),
+        },
+        BlogComponent::Heading {
+            text: format!("{} in practice", topic),
+        },
+        BlogComponent::Paragraph {
+            markdown: paragraph(1),
+        },
+        BlogComponent::Code {
+            language: "rust".to_string(),
+            code: format!(
+                "fn example_{}() -> usize {{\n    (0..{}).sum()\n}}",
+                n,
+                n % 100
+            ),
+        },
+    ];
+    if n.is_multiple_of(4) {
+        components.push(BlogComponent::Callout {
+            style: "note".to_string(),
+            markdown: paragraph(2),
+        });
+    }
+
+    // Three posts in every ten form a series; one in twenty is a draft.
+    let series = (n % 10 < 3).then(|| {
+        let group = n / 10;
+        format!("{} Deep Dive {}", TOPICS[group % TOPICS.len()], group)
+    });
+
+    BlogPostInput {
+        title: format!("{} #{}: Notes on {}", topic, n, TAGS[n % TAGS.len()]),
+        slug: format!("load-test-{:05}", n),
+        published_at: (!n.is_multiple_of(20)).then(|| load_test_date(n)),
+        cover_image: None,
+        tags,
+        series_position: series.as_ref().map(|_| (n % 10 + 1) as i32),
+        series,
+        components,
+    }
+}
+
+/// One post every seven hours, counting back from the start of 2025.
+fn load_test_date(n: usize) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() - Duration::hours(7 * n as i64)
+}
@@ -0,0 +1,203 @@
+//! Post slugs: generating them from titles and checking the ones authors
+//! pick. A slug is lowercase ASCII letters and digits in words joined by
+//! single hyphens, so it reads the same in every URL, file name and shell.
+
+/// Longest slug generated from a title. Slugs chosen by hand may use the
+/// full column width, [`MAX_SLUG_LENGTH`].
+pub const MAX_GENERATED_SLUG_LENGTH: usize = 80;
+pub const MAX_SLUG_LENGTH: usize = 255;
+
+/// Used when nothing of a title survives transliteration, and appended
+/// to generated slugs that are reserved.
+const FALLBACK_SLUG: &str = "post";
+
+/// Slugs taken by other pages under `/blog` and `/api/blog`. Numbers are
+/// reserved too, for the archive years.
+const RESERVED_SLUGS: &[&str] = &["archive"];
+
+/// Words dropped from generated slugs unless the title has nothing else.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "in", "into", "is",
+    "it", "its", "of", "on", "or", "the", "to", "was", "with",
+];
+
+/// A slug for `text`: transliterated to ASCII, stop words dropped and cut
+/// at a word boundary to at most [`MAX_GENERATED_SLUG_LENGTH`] bytes.
+pub fn slugify(text: &str) -> String {
+    let ascii = deunicode::deunicode_with_tofu(text, " ").to_lowercase();
+    let words: Vec<&str> = ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let significant: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| !STOP_WORDS.contains(word))
+        .collect();
+    let words = if significant.is_empty() {
+        words
+    } else {
+        significant
+    };
+
+    let slug = truncate(&words, MAX_GENERATED_SLUG_LENGTH);
+    if slug.is_empty() {
+        FALLBACK_SLUG.to_string()
+    } else if is_reserved(&slug) {
+        let stem = &slug[..slug
+            .len()
+            .min(MAX_GENERATED_SLUG_LENGTH - FALLBACK_SLUG.len() - 1)];
+        format!("{}-{}", stem, FALLBACK_SLUG)
+    } else {
+        slug
+    }
+}
+
+/// `text` itself when it is already a valid slug, as file names of posts
+/// usually are, otherwise [`slugify`]`(text)`.
+pub fn to_slug(text: &str) -> String {
+    match check_slug(text) {
+        Ok(()) => text.to_string(),
+        Err(_) => slugify(text),
+    }
+}
+
+/// The first of `base`, `base-2`, `base-3`, ... that `is_taken` rejects,
+/// shortening `base` where needed to keep the suffix within
+/// [`MAX_GENERATED_SLUG_LENGTH`].
+pub fn unique_slug(base: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!("-{}", n);
+            let words: Vec<&str> = base.split('-').collect();
+            let stem = truncate(&words, MAX_GENERATED_SLUG_LENGTH - suffix.len());
+            format!("{}{}", stem, suffix)
+        })
+        .find(|slug| !is_taken(slug))
+        .expect("some suffix is free")
+}
+
+/// Check a slug chosen by hand, explaining what is wrong with it.
+pub fn check_slug(slug: &str) -> Result<(), String> {
+    if slug.len() > MAX_SLUG_LENGTH {
+        return Err(format!("must be at most {} characters", MAX_SLUG_LENGTH));
+    }
+    let valid = slug
+        .split('-')
+        .all(|word| !word.is_empty() && word.bytes().all(is_slug_byte));
+    if valid && is_reserved(slug) {
+        return Err(format!(
+            "is reserved for another page; try `{}-{}`",
+            slug, FALLBACK_SLUG
+        ));
+    }
+    if !valid {
+        return Err(format!(
+            "may only contain lowercase letters a-z and digits, in words joined by single \
+             hyphens; try `{}`",
+            slugify(slug)
+        ));
+    }
+    Ok(())
+}
+
+fn is_reserved(slug: &str) -> bool {
+    RESERVED_SLUGS.contains(&slug) || slug.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn is_slug_byte(byte: u8) -> bool {
+    byte.is_ascii_lowercase() || byte.is_ascii_digit()
+}
+
+/// Join as many leading `words` as fit in `max` bytes. A first word that
+/// is too long on its own is cut.
+fn truncate(words: &[&str], max: usize) -> String {
+    let mut slug = String::new();
+    for word in words {
+        let separator = usize::from(!slug.is_empty());
+        if slug.len() + separator + word.len() > max {
+            if slug.is_empty() {
+                slug.push_str(&word[..max]);
+            }
+            break;
+        }
+        if separator == 1 {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transliterates_and_drops_stop_words() {
+        assert_eq!(slugify("The Rust Book"), "rust-book");
+        assert_eq!(slugify("Über Straße & Café"), "uber-strasse-cafe");
+        assert_eq!(
+            slugify("Ownership — and  Borrowing!"),
+            "ownership-borrowing"
+        );
+        assert_eq!(slugify("The"), "the");
+        assert_eq!(slugify("!!!"), "post");
+        assert_eq!(slugify("2024"), "2024-post");
+        assert_eq!(slugify("Archive"), "archive-post");
+    }
+
+    #[test]
+    fn keeps_valid_slugs() {
+        assert_eq!(to_slug("the-rust-book"), "the-rust-book");
+        assert_eq!(to_slug("The Rust Book"), "rust-book");
+    }
+
+    #[test]
+    fn caps_length_at_a_word_boundary() {
+        let slug = slugify(&"lifetimes ".repeat(20));
+        assert!(slug.len() <= MAX_GENERATED_SLUG_LENGTH);
+        assert!(slug.ends_with("lifetimes"));
+        assert_eq!(slugify(&"x".repeat(100)).len(), MAX_GENERATED_SLUG_LENGTH);
+    }
+
+    #[test]
+    fn resolves_collisions_with_suffixes() {
+        let taken = ["rust-book", "rust-book-2"];
+        assert_eq!(
+            unique_slug("rust-book", |s| taken.contains(&s)),
+            "rust-book-3"
+        );
+        assert_eq!(unique_slug("traits", |s| taken.contains(&s)), "traits");
+
+        let long = slugify(&"lifetimes ".repeat(20));
+        let unique = unique_slug(&long, |s| s == long);
+        assert!(unique.len() <= MAX_GENERATED_SLUG_LENGTH);
+        assert!(unique.ends_with("lifetimes-2"));
+    }
+
+    #[test]
+    fn rejects_unsafe_slugs() {
+        assert!(check_slug("rust-2024-edition").is_ok());
+        for slug in [
+            "",
+            "Rust",
+            "rust--book",
+            "-rust",
+            "rust-",
+            "rust book",
+            "café",
+        ] {
+            assert!(check_slug(slug).is_err(), "{}", slug);
+        }
+        assert!(check_slug("Rust Book")
+            .unwrap_err()
+            .contains("try `rust-book`"));
+        assert!(check_slug("2024").unwrap_err().contains("try `2024-post`"));
+        assert!(check_slug("archive").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::database::Database;
+use crate::models::user::User;
+use crate::services::validation::{ValidationErrors, ValidationIssue};
+use anyhow::Result;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHasher};
+
+/// Shortest password accepted for a user.
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+
+/// Length of passwords made up by [`generate_password`].
+const GENERATED_PASSWORD_LENGTH: usize = 24;
+
+pub struct UserService {
+    db: Database,
+}
+
+impl UserService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Create a user. Invalid input and an email already in use are
+    /// returned as [`ValidationErrors`] inside the `anyhow::Error`.
+    pub async fn create_user(&self, email: &str, name: &str, password: &str) -> Result<User> {
+        let email = email.trim();
+        let name = name.trim();
+
+        let mut errors = Vec::new();
+        if !is_email(email) {
+            errors.push(issue("$.email", "expected an email address"));
+        }
+        if name.is_empty() {
+            errors.push(issue("$.name", "must not be empty"));
+        }
+        if let Err(message) = check_password(password) {
+            errors.push(issue("$.password", message));
+        }
+        if errors.is_empty() && self.db.get_user_by_email(email).await?.is_some() {
+            errors.push(issue("$.email", format!("{} is already in use", email)));
+        }
+        if !errors.is_empty() {
+            return Err(ValidationErrors { errors }.into());
+        }
+
+        self.db
+            .insert_user(email, name, &hash_password(password)?)
+            .await
+    }
+
+    /// Replace the password of the user with `email`. Returns `None` when
+    /// there is no such user.
+    pub async fn reset_password(&self, email: &str, password: &str) -> Result<Option<User>> {
+        if let Err(message) = check_password(password) {
+            return Err(ValidationErrors::single("$.password", message).into());
+        }
+        self.db
+            .update_user_password(email.trim(), &hash_password(password)?)
+            .await
+    }
+
+    /// The user with `email`, ignoring case.
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        self.db.get_user_by_email(email.trim()).await
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<User>> {
+        self.db.list_users().await
+    }
+}
+
+/// A random password of letters, digits, `-` and `_`.
+pub fn generate_password() -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut bytes = [0u8; GENERATED_PASSWORD_LENGTH];
+    OsRng.fill_bytes(&mut bytes);
+    // 64 symbols, so masking keeps every symbol equally likely.
+    bytes
+        .iter()
+        .map(|byte| ALPHABET[(byte & 63) as usize] as char)
+        .collect()
+}
+
+fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|error| anyhow::anyhow!("Failed to hash password: {}", error))?;
+    Ok(hash.to_string())
+}
+
+fn check_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!(
+            "must be at least {} characters long",
+            MIN_PASSWORD_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn is_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn issue(path: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        component: None,
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
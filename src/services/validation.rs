@@ -0,0 +1,746 @@
+use crate::models::blog::{BlogComponent, BlogPostInput};
+use crate::services::slugs::check_slug;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// How deep `tabs`, `details` and `steps` may nest inside each other.
+pub const MAX_NESTING_DEPTH: usize = 4;
+/// Most components a single post may contain at the top level.
+pub const MAX_COMPONENTS: usize = 500;
+/// Longest text accepted in any single field, in bytes.
+pub const MAX_TEXT_LENGTH: usize = 100_000;
+pub const MAX_TABLE_ROWS: usize = 1_000;
+pub const MAX_TABLE_COLUMNS: usize = 50;
+
+pub const CALLOUT_STYLES: &[&str] = &["info", "warning", "success", "error", "note"];
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ValidationIssue {
+    /// Index of the top-level component the issue belongs to, if any.
+    pub component: Option<usize>,
+    /// JSON path of the offending value, e.g. `$.components[2].tabs[0].label`.
+    pub path: String,
+    pub message: String,
+}
+
+/// Every problem found in a post, returned together so authors can fix
+/// them in one go.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<ValidationIssue>,
+}
+
+impl ValidationErrors {
+    pub fn single(path: &str, message: impl Into<String>) -> Self {
+        ValidationErrors {
+            errors: vec![ValidationIssue {
+                component: None,
+                path: path.to_string(),
+                message: message.into(),
+            }],
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self
+            .errors
+            .iter()
+            .map(|issue| format!("{}: {}", issue.path, issue.message))
+            .collect();
+        write!(f, "validation failed: {}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Deserialize a post from raw JSON and validate it.
+///
+/// Components are deserialized leniently, so a malformed one surfaces as a
+/// [`BlogComponent::Unknown`] and is reported with its exact path by
+/// [`validate_post`], alongside every other issue in the post.
+pub fn parse_post_input(value: Value) -> Result<BlogPostInput, ValidationErrors> {
+    if !value.is_object() {
+        return Err(ValidationErrors::single("$", "expected a JSON object"));
+    }
+
+    let input = serde_json::from_value::<BlogPostInput>(value)
+        .map_err(|error| ValidationErrors::single("$", error.to_string()))?;
+    validate_post(&input)?;
+    Ok(input)
+}
+
+/// Semantic checks on an already deserialized post.
+pub fn validate_post(input: &BlogPostInput) -> Result<(), ValidationErrors> {
+    check_post(input, Issues::default())
+}
+
+/// [`validate_post`] for a post that was stored before, e.g. one restored
+/// from a site archive. Unreadable components are kept as they are, as
+/// they are everywhere stored posts are read, instead of being reported.
+pub fn validate_stored_post(input: &BlogPostInput) -> Result<(), ValidationErrors> {
+    check_post(
+        input,
+        Issues {
+            keep_unknown: true,
+            ..Issues::default()
+        },
+    )
+}
+
+fn check_post(input: &BlogPostInput, mut issues: Issues) -> Result<(), ValidationErrors> {
+    if input.title.trim().is_empty() {
+        issues.push(None, "$.title".to_string(), "must not be empty");
+    }
+    if input.title.chars().count() > 255 {
+        issues.push(
+            None,
+            "$.title".to_string(),
+            "must be at most 255 characters",
+        );
+    }
+    // An empty slug is generated from the title when the post is saved.
+    if !input.slug.is_empty() {
+        if let Err(message) = check_slug(&input.slug) {
+            issues.push(None, "$.slug".to_string(), message);
+        }
+    }
+    if let Some(cover_image) = &input.cover_image {
+        issues.check_url(None, "$.cover_image", cover_image, UrlKind::Media);
+    }
+    if input.components.len() > MAX_COMPONENTS {
+        issues.push(
+            None,
+            "$.components".to_string(),
+            format!("must contain at most {} components", MAX_COMPONENTS),
+        );
+    }
+
+    validate_list(&input.components, "$.components", None, 0, &mut issues);
+    issues.into_result()
+}
+
+pub fn validate_components(components: &[BlogComponent]) -> Result<(), ValidationErrors> {
+    let mut issues = Issues::default();
+    validate_list(components, "$.components", None, 0, &mut issues);
+    issues.into_result()
+}
+
+fn validate_list(
+    components: &[BlogComponent],
+    path: &str,
+    top_level: Option<usize>,
+    depth: usize,
+    issues: &mut Issues,
+) {
+    for (index, component) in components.iter().enumerate() {
+        validate_component(
+            component,
+            &format!("{}[{}]", path, index),
+            top_level.or(Some(index)),
+            depth,
+            issues,
+        );
+    }
+}
+
+fn validate_component(
+    component: &BlogComponent,
+    path: &str,
+    index: Option<usize>,
+    depth: usize,
+    issues: &mut Issues,
+) {
+    let is_container = matches!(
+        component,
+        BlogComponent::Tabs { .. } | BlogComponent::Details { .. } | BlogComponent::Steps { .. }
+    );
+    if is_container && depth >= MAX_NESTING_DEPTH {
+        issues.push(
+            index,
+            path.to_string(),
+            format!("nesting deeper than {} levels", MAX_NESTING_DEPTH),
+        );
+        return;
+    }
+
+    let field = |name: &str| format!("{}.{}", path, name);
+
+    match component {
+        BlogComponent::Heading { text } => {
+            issues.check_text(index, &field("text"), text, true);
+        }
+        BlogComponent::Paragraph { markdown } => {
+            issues.check_text(index, &field("markdown"), markdown, true);
+        }
+        BlogComponent::Code { language, code } => {
+            issues.check_text(index, &field("language"), language, false);
+            issues.check_text(index, &field("code"), code, true);
+        }
+        BlogComponent::Callout { style, markdown } => {
+            if !CALLOUT_STYLES.contains(&style.as_str()) {
+                issues.push(
+                    index,
+                    field("style"),
+                    format!("must be one of: {}", CALLOUT_STYLES.join(", ")),
+                );
+            }
+            issues.check_text(index, &field("markdown"), markdown, true);
+        }
+        BlogComponent::Card {
+            title,
+            description,
+            link,
+        } => {
+            issues.check_text(index, &field("title"), title, true);
+            issues.check_text(index, &field("description"), description, false);
+            issues.check_url(index, &field("link"), link, UrlKind::Link);
+        }
+        BlogComponent::Image { src, alt, caption } => {
+            issues.check_url(index, &field("src"), src, UrlKind::Media);
+            issues.check_text(index, &field("alt"), alt, false);
+            if let Some(caption) = caption {
+                issues.check_text(index, &field("caption"), caption, false);
+            }
+        }
+        BlogComponent::Quote { text, author } => {
+            issues.check_text(index, &field("text"), text, true);
+            if let Some(author) = author {
+                issues.check_text(index, &field("author"), author, false);
+            }
+        }
+        BlogComponent::Tabs { tabs } => {
+            if tabs.is_empty() {
+                issues.push(index, field("tabs"), "must contain at least one tab");
+            }
+            for (tab_index, tab) in tabs.iter().enumerate() {
+                let tab_path = format!("{}.tabs[{}]", path, tab_index);
+                issues.check_text(index, &format!("{}.label", tab_path), &tab.label, true);
+                let components_path = format!("{}.components", tab_path);
+                validate_list(&tab.components, &components_path, index, depth + 1, issues);
+            }
+        }
+        BlogComponent::Details {
+            summary,
+            markdown,
+            components,
+            ..
+        } => {
+            issues.check_text(index, &field("summary"), summary, true);
+            match markdown {
+                Some(markdown) => issues.check_text(index, &field("markdown"), markdown, false),
+                None if components.is_empty() => issues.push(
+                    index,
+                    path.to_string(),
+                    "must have a markdown body or nested components",
+                ),
+                None => {}
+            }
+            validate_list(components, &field("components"), index, depth + 1, issues);
+        }
+        BlogComponent::Steps { steps } => {
+            if steps.is_empty() {
+                issues.push(index, field("steps"), "must contain at least one step");
+            }
+            for (step_index, step) in steps.iter().enumerate() {
+                let step_path = format!("{}.steps[{}]", path, step_index);
+                issues.check_text(index, &format!("{}.title", step_path), &step.title, true);
+                if let Some(markdown) = &step.markdown {
+                    issues.check_text(index, &format!("{}.markdown", step_path), markdown, false);
+                }
+                let components_path = format!("{}.components", step_path);
+                validate_list(&step.components, &components_path, index, depth + 1, issues);
+            }
+        }
+        BlogComponent::Math { latex } => {
+            issues.check_text(index, &field("latex"), latex, true);
+        }
+        BlogComponent::Diagram { source, caption } => {
+            issues.check_text(index, &field("source"), source, true);
+            if let Some(caption) = caption {
+                issues.check_text(index, &field("caption"), caption, false);
+            }
+        }
+        BlogComponent::Video {
+            src,
+            poster,
+            captions,
+        } => {
+            issues.check_url(index, &field("src"), src, UrlKind::SelfHosted);
+            if let Some(poster) = poster {
+                issues.check_url(index, &field("poster"), poster, UrlKind::SelfHosted);
+            }
+            for (track_index, track) in captions.iter().enumerate() {
+                let track_path = format!("{}.captions[{}]", path, track_index);
+                issues.check_url(
+                    index,
+                    &format!("{}.src", track_path),
+                    &track.src,
+                    UrlKind::SelfHosted,
+                );
+                issues.check_text(
+                    index,
+                    &format!("{}.srclang", track_path),
+                    &track.srclang,
+                    true,
+                );
+                issues.check_text(index, &format!("{}.label", track_path), &track.label, true);
+            }
+        }
+        BlogComponent::Embed { provider, url, .. } => {
+            if provider.embed_url(url).is_none() {
+                issues.push(
+                    index,
+                    field("url"),
+                    format!("is not a supported {} URL", provider.display_name()),
+                );
+            }
+        }
+        BlogComponent::Table {
+            headers,
+            rows,
+            alignment,
+            ..
+        } => {
+            if headers.is_empty() {
+                issues.push(index, field("headers"), "must not be empty");
+            }
+            if headers.len() > MAX_TABLE_COLUMNS {
+                issues.push(
+                    index,
+                    field("headers"),
+                    format!("must have at most {} columns", MAX_TABLE_COLUMNS),
+                );
+            }
+            let text_length: usize = headers
+                .iter()
+                .chain(rows.iter().flatten())
+                .map(String::len)
+                .sum();
+            if text_length > MAX_TEXT_LENGTH {
+                issues.push(
+                    index,
+                    field("rows"),
+                    format!("must hold at most {} bytes of text", MAX_TEXT_LENGTH),
+                );
+            }
+            if rows.len() > MAX_TABLE_ROWS {
+                issues.push(
+                    index,
+                    field("rows"),
+                    format!("must have at most {} rows", MAX_TABLE_ROWS),
+                );
+            }
+            if alignment.len() > headers.len() {
+                issues.push(
+                    index,
+                    field("alignment"),
+                    "has more entries than there are columns",
+                );
+            }
+            for (column, header) in headers.iter().enumerate() {
+                let header_path = format!("{}.headers[{}]", path, column);
+                issues.check_text(index, &header_path, header, false);
+            }
+            for (row_index, row) in rows.iter().enumerate() {
+                for (column, cell) in row.iter().enumerate() {
+                    let cell_path = format!("{}.rows[{}][{}]", path, row_index, column);
+                    issues.check_text(index, &cell_path, cell, false);
+                }
+                if row.len() != headers.len() {
+                    issues.push(
+                        index,
+                        format!("{}.rows[{}]", path, row_index),
+                        format!(
+                            "has {} cells but the table has {} columns",
+                            row.len(),
+                            headers.len()
+                        ),
+                    );
+                }
+            }
+        }
+        BlogComponent::Unknown(unknown) => {
+            if !issues.keep_unknown {
+                issues.push(index, path.to_string(), unknown.error.clone());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum UrlKind {
+    /// Hyperlinks: http(s), mailto, site-relative paths and fragments.
+    Link,
+    /// Images: http(s) or site-relative paths.
+    Media,
+    /// Files served by this site: site-relative paths only.
+    SelfHosted,
+}
+
+#[derive(Default)]
+struct Issues {
+    found: Vec<ValidationIssue>,
+    /// Let unreadable components through; see [`validate_stored_post`].
+    keep_unknown: bool,
+}
+
+impl Issues {
+    fn push(&mut self, component: Option<usize>, path: String, message: impl Into<String>) {
+        self.found.push(ValidationIssue {
+            component,
+            path,
+            message: message.into(),
+        });
+    }
+
+    fn check_text(&mut self, component: Option<usize>, path: &str, value: &str, required: bool) {
+        if required && value.trim().is_empty() {
+            self.push(component, path.to_string(), "must not be empty");
+        }
+        if value.len() > MAX_TEXT_LENGTH {
+            self.push(
+                component,
+                path.to_string(),
+                format!("must be at most {} bytes", MAX_TEXT_LENGTH),
+            );
+        }
+    }
+
+    fn check_url(&mut self, component: Option<usize>, path: &str, value: &str, kind: UrlKind) {
+        let value = value.trim();
+        if value.is_empty() {
+            self.push(component, path.to_string(), "must not be empty");
+            return;
+        }
+
+        let site_relative = is_local_path(value);
+        let allowed = match kind {
+            UrlKind::SelfHosted => site_relative,
+            UrlKind::Media => site_relative || has_scheme(value, &["http", "https"]),
+            UrlKind::Link => {
+                site_relative
+                    || value.starts_with('#')
+                    || has_scheme(value, &["http", "https", "mailto"])
+            }
+        };
+
+        if !allowed {
+            let message = match kind {
+                UrlKind::SelfHosted => "must be a path to a self-hosted file, e.g. /static/...",
+                UrlKind::Media => "must be an http(s) URL or a site-relative path",
+                UrlKind::Link => {
+                    "must be an http(s) or mailto URL, a site-relative path or a fragment"
+                }
+            };
+            self.push(component, path.to_string(), message);
+        }
+    }
+
+    fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.found.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { errors: self.found })
+        }
+    }
+}
+
+/// Whether `path` is a path on this site. Browsers read `\` as `/`, so
+/// `/\example.com` would leave it just like `//example.com`.
+pub fn is_local_path(path: &str) -> bool {
+    path.starts_with('/') && !path.starts_with("//") && !path.contains('\\')
+}
+
+fn has_scheme(value: &str, schemes: &[&str]) -> bool {
+    url::Url::parse(value)
+        .map(|url| schemes.contains(&url.scheme()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The issues of a post with `components`, as `(component, path)`.
+    fn issues(components: Value) -> Vec<(Option<usize>, String)> {
+        let post = json!({ "title": "A post", "components": components });
+        match parse_post_input(post) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors
+                .errors
+                .into_iter()
+                .map(|issue| (issue.component, issue.path))
+                .collect(),
+        }
+    }
+
+    fn single(component: Value) -> Vec<(Option<usize>, String)> {
+        issues(json!([component]))
+    }
+
+    fn path(component: Option<usize>, path: &str) -> (Option<usize>, String) {
+        (component, path.to_string())
+    }
+
+    #[test]
+    fn accepts_a_valid_post() {
+        let components = json!([
+            { "type": "heading", "text": "Intro" },
+            { "type": "paragraph", "markdown": "Hello" },
+            { "type": "callout", "style": "note", "markdown": "Careful" },
+            { "type": "card", "title": "Docs", "description": "", "link": "https://docs.rs" },
+            { "type": "image", "src": "/static/a.png", "alt": "A" },
+            { "type": "video", "src": "/static/a.mp4", "captions": [
+                { "src": "/static/a.vtt", "srclang": "en", "label": "English" }
+            ] },
+            { "type": "embed", "provider": "youtube", "url": "https://youtu.be/dQw4w9WgXcQ" },
+            { "type": "table", "headers": ["a", "b"], "rows": [["1", "2"]] },
+        ]);
+        assert_eq!(issues(components), []);
+    }
+
+    #[test]
+    fn rejects_a_post_that_is_not_an_object() {
+        let errors = parse_post_input(json!(["not", "a", "post"])).unwrap_err();
+        assert_eq!(errors.errors[0].path, "$");
+    }
+
+    #[test]
+    fn checks_the_post_fields() {
+        let post = json!({
+            "title": " ",
+            "slug": "Not A Slug",
+            "cover_image": "javascript:alert(1)",
+            "components": [],
+        });
+        let errors = parse_post_input(post).unwrap_err();
+        let paths: Vec<&str> = errors
+            .errors
+            .iter()
+            .map(|issue| issue.path.as_str())
+            .collect();
+        assert_eq!(paths, ["$.title", "$.slug", "$.cover_image"]);
+        assert!(errors.errors.iter().all(|issue| issue.component.is_none()));
+
+        let title = "x".repeat(256);
+        let errors = parse_post_input(json!({ "title": title })).unwrap_err();
+        assert_eq!(errors.errors[0].message, "must be at most 255 characters");
+    }
+
+    #[test]
+    fn requires_text_fields() {
+        assert_eq!(
+            single(json!({ "type": "heading", "text": "  " })),
+            [path(Some(0), "$.components[0].text")]
+        );
+        assert_eq!(
+            single(json!({ "type": "code", "language": "", "code": "" })),
+            [path(Some(0), "$.components[0].code")]
+        );
+        assert_eq!(
+            single(json!({ "type": "tabs", "tabs": [] })),
+            [path(Some(0), "$.components[0].tabs")]
+        );
+    }
+
+    #[test]
+    fn checks_callout_styles() {
+        let errors = parse_post_input(json!({
+            "title": "A post",
+            "components": [{ "type": "callout", "style": "shout", "markdown": "Hi" }],
+        }))
+        .unwrap_err();
+        assert_eq!(errors.errors[0].path, "$.components[0].style");
+        assert_eq!(
+            errors.errors[0].message,
+            "must be one of: info, warning, success, error, note"
+        );
+    }
+
+    #[test]
+    fn checks_url_schemes() {
+        let card =
+            |link: &str| json!({ "type": "card", "title": "T", "description": "", "link": link });
+        for link in [
+            "https://example.com",
+            "mailto:a@example.com",
+            "/blog",
+            "#top",
+        ] {
+            assert_eq!(single(card(link)), [], "{}", link);
+        }
+        for link in [
+            "javascript:alert(1)",
+            "ftp://example.com",
+            "//evil.example",
+            "/\\evil.example",
+        ] {
+            assert_eq!(
+                single(card(link)),
+                [path(Some(0), "$.components[0].link")],
+                "{}",
+                link
+            );
+        }
+
+        let image = |src: &str| json!({ "type": "image", "src": src, "alt": "" });
+        assert_eq!(single(image("https://cdn.example/a.png")), []);
+        for src in ["data:image/png;base64,AAAA", "/\\evil.example/a.png", ""] {
+            assert_eq!(
+                single(image(src)),
+                [path(Some(0), "$.components[0].src")],
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn self_hosted_files_stay_on_this_site() {
+        let video = |src: &str, track: &str| {
+            json!({ "type": "video", "src": src, "captions": [
+                { "src": track, "srclang": "en", "label": "English" }
+            ] })
+        };
+        assert_eq!(
+            single(video("https://cdn.example/a.mp4", "/\\evil.example/a.vtt")),
+            [
+                path(Some(0), "$.components[0].src"),
+                path(Some(0), "$.components[0].captions[0].src"),
+            ]
+        );
+        assert_eq!(
+            single(video("/\\evil.example/a.mp4", "/static/a.vtt")),
+            [path(Some(0), "$.components[0].src")]
+        );
+    }
+
+    #[test]
+    fn checks_embed_urls() {
+        let embed = json!({ "type": "embed", "provider": "vimeo", "url": "https://youtu.be/x" });
+        assert_eq!(single(embed), [path(Some(0), "$.components[0].url")]);
+    }
+
+    #[test]
+    fn limits_sizes() {
+        let long = "x".repeat(MAX_TEXT_LENGTH + 1);
+        assert_eq!(
+            single(json!({ "type": "paragraph", "markdown": long })),
+            [path(Some(0), "$.components[0].markdown")]
+        );
+
+        let paragraphs = vec![json!({ "type": "paragraph", "markdown": "x" }); MAX_COMPONENTS + 1];
+        assert_eq!(issues(json!(paragraphs)), [path(None, "$.components")]);
+
+        let columns = vec!["c"; MAX_TABLE_COLUMNS + 1];
+        let rows = vec![vec!["1"; 1]; MAX_TABLE_ROWS + 1];
+        let table = json!({ "type": "table", "headers": ["a"], "rows": rows });
+        assert_eq!(single(table), [path(Some(0), "$.components[0].rows")]);
+        let table = json!({ "type": "table", "headers": columns, "rows": [] });
+        assert_eq!(single(table), [path(Some(0), "$.components[0].headers")]);
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let mut component = json!({ "type": "paragraph", "markdown": "Deep" });
+        for _ in 0..=MAX_NESTING_DEPTH {
+            component =
+                json!({ "type": "tabs", "tabs": [{ "label": "Tab", "components": [component] }] });
+        }
+        let nested = (0..MAX_NESTING_DEPTH)
+            .map(|_| ".tabs[0].components[0]")
+            .collect::<String>();
+        assert_eq!(
+            single(component),
+            [path(Some(0), &format!("$.components[0]{}", nested))]
+        );
+    }
+
+    #[test]
+    fn reports_nested_issues_under_their_top_level_component() {
+        let components = json!([
+            { "type": "heading", "text": "Fine" },
+            { "type": "steps", "steps": [
+                { "title": "One" },
+                { "title": "Two", "components": [
+                    { "type": "details", "summary": "More", "components": [
+                        { "type": "quote", "text": "" },
+                    ] },
+                ] },
+            ] },
+        ]);
+        assert_eq!(
+            issues(components),
+            [path(
+                Some(1),
+                "$.components[1].steps[1].components[0].components[0].text"
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_every_malformed_component_at_its_path() {
+        let components = json!([
+            { "type": "paragraph", "markdown": "Fine" },
+            { "type": "hologram" },
+            { "type": "tabs", "tabs": [{ "label": "Tab", "components": [{ "type": "heading" }] }] },
+        ]);
+        assert_eq!(
+            issues(components),
+            [
+                path(Some(1), "$.components[1]"),
+                path(Some(2), "$.components[2].tabs[0].components[0]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn video_posters_are_self_hosted() {
+        let video =
+            |poster: &str| json!([{ "type": "video", "src": "/static/a.mp4", "poster": poster }]);
+        assert!(issues(video("/static/a.jpg")).is_empty());
+        assert_eq!(
+            issues(video("https://cdn.example/a.jpg")),
+            [(Some(0), "$.components[0].poster".to_string())]
+        );
+    }
+
+    #[test]
+    fn stored_posts_keep_unreadable_components() {
+        let input: BlogPostInput = serde_json::from_value(json!({
+            "title": "A post",
+            "components": [
+                { "type": "tabs", "tabs": [
+                    { "label": "Tab", "components": [{ "type": "sparkle" }] },
+                ] },
+                { "type": "heading", "text": "" },
+            ],
+        }))
+        .unwrap();
+
+        let paths = |result: Result<(), ValidationErrors>| -> Vec<String> {
+            result
+                .unwrap_err()
+                .errors
+                .into_iter()
+                .map(|issue| issue.path)
+                .collect()
+        };
+        assert_eq!(
+            paths(validate_post(&input)),
+            [
+                "$.components[0].tabs[0].components[0]",
+                "$.components[1].text"
+            ]
+        );
+        assert_eq!(
+            paths(validate_stored_post(&input)),
+            ["$.components[1].text"]
+        );
+    }
+}
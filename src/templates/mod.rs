@@ -1,21 +1,31 @@
-use crate::models::blog::{BlogComponent, BlogPost, BlogPostSummary};
+use crate::config::Config;
+use crate::models::blog::{
+    ArchivePeriod, ArchiveYear, BlogComponent, BlogPost, BlogPostSummary, ColumnAlignment,
+    EmbedProvider, Homepage,
+};
 use ammonia::Builder;
 use chrono::{DateTime, Utc};
 use minijinja::{Environment, Error, Value};
 use pulldown_cmark::{html, Options, Parser};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+mod rendered;
 
 pub struct TemplateEngine {
     env: Environment<'static>,
-    markdown_parser: MarkdownParser,
+    site_url: String,
 }
 
 pub struct MarkdownParser {
     options: Options,
     sanitizer: ammonia::Builder<'static>,
+    embed_sanitizer: ammonia::Builder<'static>,
+    embed_providers: Vec<EmbedProvider>,
 }
 
 impl MarkdownParser {
-    pub fn new() -> Self {
+    pub fn new(embed_providers: Vec<EmbedProvider>) -> Self {
         let mut options = Options::empty();
         options.insert(Options::ENABLE_STRIKETHROUGH);
         options.insert(Options::ENABLE_TABLES);
@@ -65,7 +75,50 @@ impl MarkdownParser {
             .link_rel(Some("noopener noreferrer"))
             .url_relative(ammonia::UrlRelative::PassThrough);
 
-        Self { options, sanitizer }
+        // Embed facades are the only markup with buttons and iframe
+        // sources, and may only point at the iframe hosts of allowed
+        // providers; anything else loses its source and stays inert.
+        let mut embed_sanitizer = Builder::empty();
+        embed_sanitizer
+            .add_tags(&["div", "span", "button", "a"])
+            .add_tag_attributes("div", &["data-embed-src", "data-embed-title"])
+            .add_tag_attributes("button", &["type"])
+            .add_tag_attributes("a", &["href", "target"])
+            .add_generic_attributes(&["class"])
+            .url_schemes(["http", "https"].into())
+            .link_rel(Some("noopener noreferrer"));
+        let allowed_hosts: Vec<&'static str> = embed_providers
+            .iter()
+            .map(EmbedProvider::embed_host)
+            .collect();
+        embed_sanitizer.attribute_filter(move |_element, attribute, value| {
+            if attribute != "data-embed-src" {
+                return Some(Cow::Borrowed(value));
+            }
+            let host = url::Url::parse(value)
+                .ok()
+                .filter(|url| url.scheme() == "https")
+                .and_then(|url| url.host_str().map(str::to_owned))?;
+            allowed_hosts
+                .contains(&host.as_str())
+                .then_some(Cow::Borrowed(value))
+        });
+
+        Self {
+            options,
+            sanitizer,
+            embed_sanitizer,
+            embed_providers,
+        }
+    }
+
+    pub fn allows_embed(&self, provider: EmbedProvider) -> bool {
+        self.embed_providers.contains(&provider)
+    }
+
+    /// Sanitize the facade of an embed, which carries a third-party URL.
+    pub fn sanitize_embed(&self, html: &str) -> String {
+        self.embed_sanitizer.clean(html).to_string()
     }
 
     pub fn parse(&self, markdown: &str) -> String {
@@ -102,7 +155,7 @@ impl MarkdownParser {
 }
 
 impl TemplateEngine {
-    pub fn new() -> Result<Self, Error> {
+    pub fn new(config: &Config) -> Result<Self, Error> {
         let mut env = Environment::new();
 
         // Load templates
@@ -111,25 +164,48 @@ impl TemplateEngine {
         env.add_template("blog_list.html", include_str!("./blog_list.html"))?;
         env.add_template("blog_post.html", include_str!("./blog_post.html"))?;
         env.add_template("contact.html", include_str!("./contact.html"))?;
-
-        let markdown_parser = MarkdownParser::new();
+        env.add_template(
+            "archive_sidebar.html",
+            include_str!("./archive_sidebar.html"),
+        )?;
+        env.add_template("search.html", include_str!("./search.html"))?;
+        env.add_template("error.html", include_str!("./error.html"))?;
+        env.add_template("404.html", include_str!("./404.html"))?;
+        env.add_template("500.html", include_str!("./500.html"))?;
+        env.add_template("api_docs.html", include_str!("./api_docs.html"))?;
+
+        let markdown_parser = Arc::new(MarkdownParser::new(config.embed_providers.clone()));
 
         // Add custom filters
-        env.add_filter("render_component", render_component_filter);
-        env.add_filter("markdown_to_html", markdown_to_html_filter);
+        let parser = markdown_parser.clone();
+        env.add_filter(
+            "render_component",
+            move |component: Value, preview: Option<bool>| {
+                render_component_filter(&parser, component, preview.unwrap_or(false))
+            },
+        );
+        let parser = markdown_parser;
+        env.add_filter("markdown_to_html", move |value: Value| {
+            markdown_to_html_filter(&parser, value)
+        });
         env.add_filter("date", date_filter);
+        env.add_filter("month_name", month_name_filter);
 
         Ok(TemplateEngine {
             env,
-            markdown_parser,
+            site_url: config.site_url.clone(),
         })
     }
 
-    pub fn render_home(&self) -> Result<String, Error> {
+    pub fn render_home(&self, homepage: &Homepage) -> Result<String, Error> {
         let template = self.env.get_template("home.html")?;
         template.render(minijinja::context! {
             title => "ruststack - Master Rust Backend Development",
-            description => "Learn Rust backend development with practical tutorials, courses, and resources."
+            description => "Learn Rust backend development with practical tutorials, courses, and resources.",
+            featured => homepage.featured,
+            latest => homepage.latest,
+            series => homepage.series,
+            tags => homepage.tags,
         })
     }
 
@@ -138,11 +214,42 @@ impl TemplateEngine {
         posts: &[BlogPostSummary],
         page: usize,
         total_pages: usize,
+        archive: &[ArchiveYear],
     ) -> Result<String, Error> {
         let template = self.env.get_template("blog_list.html")?;
         template.render(minijinja::context! {
             title => "Blog - ruststack",
+            heading => "Blog",
+            subheading => "Practical Rust backend development tutorials and insights",
+            list_path => "/blog",
+            posts => posts,
+            archive => archive,
+            current_page => page,
+            total_pages => total_pages,
+            has_prev => page > 1,
+            has_next => page < total_pages,
+            prev_page => if page > 1 { page - 1 } else { 1 },
+            next_page => if page < total_pages { page + 1 } else { total_pages }
+        })
+    }
+
+    /// The blog list limited to the posts of a year or month.
+    pub fn render_archive(
+        &self,
+        period: &ArchivePeriod,
+        posts: &[BlogPostSummary],
+        page: usize,
+        total_pages: usize,
+        archive: &[ArchiveYear],
+    ) -> Result<String, Error> {
+        let label = period.label();
+        let template = self.env.get_template("blog_list.html")?;
+        template.render(minijinja::context! {
+            title => format!("Posts from {} - ruststack", label),
+            heading => format!("Posts from {}", label),
+            list_path => period.path(),
             posts => posts,
+            archive => archive,
             current_page => page,
             total_pages => total_pages,
             has_prev => page > 1,
@@ -152,11 +259,31 @@ impl TemplateEngine {
         })
     }
 
-    pub fn render_blog_post(&self, post: &BlogPost) -> Result<String, Error> {
+    pub fn render_blog_post(
+        &self,
+        post: &BlogPost,
+        related_posts: &[BlogPostSummary],
+        preview: bool,
+    ) -> Result<String, Error> {
+        let template = self.env.get_template("blog_post.html")?;
+        template.render(minijinja::context! {
+            title => format!("{} - ruststack", post.title),
+            post => post,
+            related_posts => related_posts,
+            preview => preview,
+        })
+    }
+
+    /// A post as a self-contained page for export. Site-relative links and
+    /// media resolve against the public site URL.
+    pub fn render_standalone_post(&self, post: &BlogPost) -> Result<String, Error> {
         let template = self.env.get_template("blog_post.html")?;
         template.render(minijinja::context! {
             title => format!("{} - ruststack", post.title),
             post => post,
+            related_posts => Vec::<BlogPostSummary>::new(),
+            preview => false,
+            base_url => &self.site_url,
         })
     }
 
@@ -167,12 +294,73 @@ impl TemplateEngine {
         })
     }
 
-    pub fn _parse_markdown(&self, markdown: &str) -> String {
-        self.markdown_parser.parse(markdown)
+    pub fn render_search(&self, query: &str, posts: &[BlogPostSummary]) -> Result<String, Error> {
+        let template = self.env.get_template("search.html")?;
+        template.render(minijinja::context! {
+            title => "Search - ruststack",
+            query => query,
+            posts => posts,
+        })
+    }
+
+    /// Swagger UI for the OpenAPI document at `spec_url`.
+    pub fn render_api_docs(&self, spec_url: &str) -> Result<String, Error> {
+        let template = self.env.get_template("api_docs.html")?;
+        template.render(minijinja::context! {
+            title => "API docs - ruststack",
+            spec_url => spec_url,
+        })
+    }
+
+    /// The 404 page, with a search box filled in with `query` and posts the
+    /// reader may have been looking for.
+    pub fn render_not_found(
+        &self,
+        detail: &str,
+        query: &str,
+        suggestions: &[BlogPostSummary],
+        request_id: Option<String>,
+    ) -> Result<String, Error> {
+        let template = self.env.get_template("404.html")?;
+        template.render(minijinja::context! {
+            title => "Page not found - ruststack",
+            detail => detail,
+            query => query,
+            suggestions => suggestions,
+            request_id => request_id,
+        })
+    }
+
+    /// The page shown for any other error response. `request_id` lets
+    /// readers quote the failed request when reporting it.
+    pub fn render_error(
+        &self,
+        status: u16,
+        reason: &str,
+        detail: &str,
+        request_id: Option<String>,
+    ) -> Result<String, Error> {
+        let name = if status >= 500 {
+            "500.html"
+        } else {
+            "error.html"
+        };
+        let template = self.env.get_template(name)?;
+        template.render(minijinja::context! {
+            title => format!("{} - ruststack", reason),
+            status => status,
+            reason => reason,
+            detail => detail,
+            request_id => request_id,
+        })
     }
 }
 
-fn render_component_filter(component: minijinja::Value) -> Result<Value, Error> {
+fn render_component_filter(
+    parser: &MarkdownParser,
+    component: minijinja::Value,
+    preview: bool,
+) -> Result<Value, Error> {
     let convert_to = serde_json::to_value(&component).map_err(|e| {
         Error::new(
             minijinja::ErrorKind::InvalidOperation,
@@ -180,22 +368,35 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
         )
     })?;
 
-    let component: BlogComponent = serde_json::from_value(convert_to).map_err(|e| {
-        Error::new(
-            minijinja::ErrorKind::InvalidOperation,
-            format!("deserialization error: {}", e),
-        )
-    })?;
+    let component = BlogComponent::from_value_lenient(convert_to);
 
-    let html_content = match component {
+    Ok(Value::from_safe_string(render_component(
+        parser, &component, preview,
+    )))
+}
+
+fn render_components(
+    parser: &MarkdownParser,
+    components: &[BlogComponent],
+    preview: bool,
+) -> String {
+    components
+        .iter()
+        .map(|component| render_component(parser, component, preview))
+        .collect()
+}
+
+/// Render a single component. In `preview` mode, components that failed to
+/// deserialize are shown as a warning instead of being left out.
+fn render_component(parser: &MarkdownParser, component: &BlogComponent, preview: bool) -> String {
+    match component {
         BlogComponent::Heading { text } => format!(
             r#"<h2 class="text-2xl font-bold text-gray-900 mb-4">{}</h2>"#,
-            html_escape(&text)
+            html_escape(text)
         ),
         BlogComponent::Paragraph { markdown } => {
             // Use the proper markdown parser
-            let parser = MarkdownParser::new();
-            let html = parser.parse(&markdown);
+            let html = parser.parse(markdown);
             format!(
                 r#"<div class="prose prose-lg max-w-none mb-6">{}</div>"#,
                 html
@@ -205,8 +406,8 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
             r#"<div class="bg-gray-900 rounded-lg p-4 mb-6 overflow-x-auto">
                     <pre><code class="language-{} text-gray-100 font-mono text-sm">{}</code></pre>
                    </div>"#,
-            html_escape(&language),
-            html_escape(&code)
+            html_escape(language),
+            html_escape(code)
         ),
         BlogComponent::Callout { style, markdown } => {
             let (bg_class, border_class, text_class, icon) = match style.as_str() {
@@ -217,8 +418,7 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
                 _ => ("bg-gray-50", "border-gray-200", "text-gray-800", "📝"),
             };
 
-            let parser = MarkdownParser::new();
-            let html = parser.parse(&markdown);
+            let html = parser.parse(markdown);
             format!(
                 r#"<div class="border-l-4 {} {} {} p-4 mb-6 rounded-r-lg">
                     <div class="flex items-start">
@@ -241,9 +441,9 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
                         Read more →
                     </a>
                    </div>"#,
-            html_escape(&title),
-            html_escape(&description),
-            html_escape(&link)
+            html_escape(title),
+            html_escape(description),
+            html_escape(link)
         ),
         BlogComponent::Image { src, alt, caption } => {
             let caption_html = caption.as_ref()
@@ -255,8 +455,8 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
                     <img src="{}" alt="{}" class="w-full rounded-lg shadow-sm" loading="lazy">
                     {}
                    </figure>"#,
-                html_escape(&src),
-                html_escape(&alt),
+                html_escape(src),
+                html_escape(alt),
                 caption_html
             )
         }
@@ -276,23 +476,292 @@ fn render_component_filter(component: minijinja::Value) -> Result<Value, Error>
                     <p class="text-lg mb-2">"{}"</p>
                     {}
                    </blockquote>"#,
-                html_escape(&text),
+                html_escape(text),
                 author_html
             )
         }
+        BlogComponent::Tabs { tabs } => {
+            let buttons: String = tabs
+                .iter()
+                .enumerate()
+                .map(|(index, tab)| {
+                    format!(
+                        r#"<button type="button" role="tab" data-tab-index="{}" class="px-4 py-2 -mb-px border-b-2 border-transparent text-sm font-medium text-gray-600 hover:text-gray-900">{}</button>"#,
+                        index,
+                        html_escape(&tab.label)
+                    )
+                })
+                .collect();
+
+            let panels: String = tabs
+                .iter()
+                .enumerate()
+                .map(|(index, tab)| {
+                    format!(
+                        r#"<div role="tabpanel" data-tab-panel="{}" class="pt-4">
+                        <h4 class="text-sm font-semibold text-gray-500 mb-2" data-tab-label>{}</h4>
+                        {}
+                       </div>"#,
+                        index,
+                        html_escape(&tab.label),
+                        render_components(parser, &tab.components, preview)
+                    )
+                })
+                .collect();
+
+            // Without JavaScript every panel is shown under its label; the
+            // script in base.html turns the markup into a tab strip.
+            format!(
+                r#"<div class="mb-6" data-tabs>
+                    <div class="flex border-b border-gray-200" role="tablist" hidden>{}</div>
+                    {}
+                   </div>"#,
+                buttons, panels
+            )
+        }
+        BlogComponent::Details {
+            summary,
+            markdown,
+            components,
+            open,
+        } => {
+            let body = markdown
+                .as_ref()
+                .map(|markdown| {
+                    format!(
+                        r#"<div class="prose prose-sm max-w-none mb-4">{}</div>"#,
+                        parser.parse(markdown)
+                    )
+                })
+                .unwrap_or_default();
+
+            format!(
+                r#"<details class="bg-white border border-gray-200 rounded-lg mb-6"{}>
+                    <summary class="cursor-pointer px-4 py-3 font-medium text-gray-900">{}</summary>
+                    <div class="px-4 pt-2 pb-4">{}{}</div>
+                   </details>"#,
+                if *open { " open" } else { "" },
+                html_escape(summary),
+                body,
+                render_components(parser, components, preview)
+            )
+        }
+        BlogComponent::Steps { steps } => {
+            let items: String = steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| {
+                    let body = step
+                        .markdown
+                        .as_ref()
+                        .map(|markdown| {
+                            format!(
+                                r#"<div class="prose prose-sm max-w-none mb-4">{}</div>"#,
+                                parser.parse(markdown)
+                            )
+                        })
+                        .unwrap_or_default();
+
+                    format!(
+                        r#"<li class="flex">
+                        <span class="flex-shrink-0 w-8 h-8 rounded-full bg-orange-600 text-white text-sm font-bold flex items-center justify-center mr-4">{}</span>
+                        <div class="flex-1 min-w-0">
+                            <h3 class="text-lg font-semibold text-gray-900 mb-2">{}</h3>
+                            {}{}
+                        </div>
+                       </li>"#,
+                        index + 1,
+                        html_escape(&step.title),
+                        body,
+                        render_components(parser, &step.components, preview)
+                    )
+                })
+                .collect();
+
+            format!(r#"<ol class="space-y-6 mb-6">{}</ol>"#, items)
+        }
+        BlogComponent::Math { latex } => rendered::render_math(latex),
+        BlogComponent::Diagram { source, caption } => {
+            rendered::render_diagram(source, caption.as_deref())
+        }
+        BlogComponent::Video {
+            src,
+            poster,
+            captions,
+        } => {
+            let poster_attr = poster
+                .as_ref()
+                .map(|p| format!(r#" poster="{}""#, html_escape(p)))
+                .unwrap_or_default();
+            let tracks: String = captions
+                .iter()
+                .map(|track| {
+                    format!(
+                        r#"<track kind="captions" src="{}" srclang="{}" label="{}"{}>"#,
+                        html_escape(&track.src),
+                        html_escape(&track.srclang),
+                        html_escape(&track.label),
+                        if track.default { " default" } else { "" }
+                    )
+                })
+                .collect();
+
+            format!(
+                r#"<figure class="mb-6">
+                    <video controls preload="metadata" class="w-full rounded-lg shadow-sm"{}>
+                        <source src="{}">
+                        {}
+                        <a href="{}" class="text-blue-600 hover:text-blue-800 underline">Download the video</a>
+                    </video>
+                   </figure>"#,
+                poster_attr,
+                html_escape(src),
+                tracks,
+                html_escape(src)
+            )
+        }
+        BlogComponent::Embed {
+            provider,
+            url,
+            title,
+        } => render_embed(parser, *provider, url, title.as_deref()),
+        BlogComponent::Table {
+            headers,
+            rows,
+            alignment,
+            caption,
+            sortable,
+        } => {
+            let align_class =
+                |column: usize| match alignment.get(column).copied().unwrap_or_default() {
+                    ColumnAlignment::Left => "text-left",
+                    ColumnAlignment::Center => "text-center",
+                    ColumnAlignment::Right => "text-right",
+                };
+
+            let header_html: String = headers
+                .iter()
+                .enumerate()
+                .map(|(column, header)| {
+                    format!(
+                        r#"<th scope="col" class="px-6 py-3 {} text-xs font-medium text-gray-500 uppercase tracking-wider">{}</th>"#,
+                        align_class(column),
+                        html_escape(header)
+                    )
+                })
+                .collect();
+
+            let rows_html: String = rows
+                .iter()
+                .map(|row| {
+                    let cells: String = row
+                        .iter()
+                        .enumerate()
+                        .map(|(column, cell)| {
+                            format!(
+                                r#"<td class="px-6 py-4 whitespace-nowrap text-sm text-gray-900 {}">{}</td>"#,
+                                align_class(column),
+                                html_escape(cell)
+                            )
+                        })
+                        .collect();
+                    format!("<tr>{}</tr>", cells)
+                })
+                .collect();
+
+            let caption_html = caption
+                .as_ref()
+                .map(|c| format!(r#"<figcaption class="text-center text-gray-600 text-sm mt-2">{}</figcaption>"#, html_escape(c)))
+                .unwrap_or_default();
+
+            format!(
+                r#"<figure class="mb-6">
+                    <div class="overflow-x-auto border border-gray-200 rounded-lg">
+                        <table class="min-w-full divide-y divide-gray-200"{}>
+                            <thead class="bg-gray-50"><tr>{}</tr></thead>
+                            <tbody class="bg-white divide-y divide-gray-200">{}</tbody>
+                        </table>
+                    </div>
+                    {}
+                   </figure>"#,
+                if *sortable { " data-sortable" } else { "" },
+                header_html,
+                rows_html,
+                caption_html
+            )
+        }
+        BlogComponent::Unknown(unknown) if preview => format!(
+            r#"<div class="border-l-4 border-yellow-400 bg-yellow-50 text-yellow-800 p-4 mb-6 rounded-r-lg">
+                <p class="text-sm font-medium mb-2">This component could not be read and is hidden from readers: {}</p>
+                <pre class="bg-gray-900 text-gray-100 p-4 rounded-lg overflow-x-auto"><code class="font-mono text-sm">{}</code></pre>
+               </div>"#,
+            html_escape(&unknown.error),
+            html_escape(&serde_json::to_string_pretty(&unknown.raw).unwrap_or_default())
+        ),
+        BlogComponent::Unknown(_) => String::new(),
+    }
+}
+
+/// Third-party content is rendered as a facade: nothing is requested from
+/// the provider until the reader clicks, at which point the script in
+/// base.html swaps in the iframe. Providers missing from the allowlist
+/// degrade to a plain link.
+fn render_embed(
+    parser: &MarkdownParser,
+    provider: EmbedProvider,
+    url: &str,
+    title: Option<&str>,
+) -> String {
+    let title = title.unwrap_or(provider.display_name());
+    let link = format!(
+        r#"<a href="{}" class="text-blue-600 hover:text-blue-800 underline" target="_blank" rel="noopener noreferrer">View “{}” on {}</a>"#,
+        html_escape(url),
+        html_escape(title),
+        provider.display_name()
+    );
+
+    let embed_url = match provider.embed_url(url) {
+        Some(embed_url) if parser.allows_embed(provider) => embed_url,
+        _ => return format!(r#"<p class="mb-6">{}</p>"#, link),
     };
 
-    Ok(Value::from_safe_string(html_content))
+    let facade = format!(
+        r#"<div class="mb-6" data-embed-src="{}" data-embed-title="{}">
+                <button type="button" class="w-full aspect-video bg-gray-900 text-white rounded-lg flex flex-col items-center justify-center hover:bg-gray-800 transition-colors">
+                    <span class="text-4xl mb-2">▶</span>
+                    <span class="font-medium">Load “{}” from {}</span>
+                    <span class="text-xs text-gray-400 mt-1">Content is loaded from {} only after you click.</span>
+                </button>
+                <div class="text-sm text-gray-600 mt-2">{}</div>
+               </div>"#,
+        html_escape(&embed_url),
+        html_escape(title),
+        html_escape(title),
+        provider.display_name(),
+        provider.embed_host(),
+        link
+    );
+
+    parser.sanitize_embed(&facade)
 }
 
-fn markdown_to_html_filter(value: minijinja::Value) -> Result<String, Error> {
+fn markdown_to_html_filter(
+    parser: &MarkdownParser,
+    value: minijinja::Value,
+) -> Result<String, Error> {
     let text = value
         .as_str()
         .ok_or_else(|| Error::new(minijinja::ErrorKind::InvalidOperation, "expected string"))?;
-    let parser = MarkdownParser::new();
     Ok(parser.parse(text))
 }
 
+/// "March" for 3.
+fn month_name_filter(month: u32) -> Result<String, Error> {
+    chrono::Month::try_from(month as u8)
+        .map(|month| month.name().to_string())
+        .map_err(|_| Error::new(minijinja::ErrorKind::InvalidOperation, "invalid month"))
+}
+
 fn date_filter(value: minijinja::Value, format: Option<&str>) -> Result<String, Error> {
     let format_str = format.unwrap_or("%Y-%m-%d %H:%M:%S");
 
@@ -328,3 +797,88 @@ fn html_escape(text: &str) -> String {
         .replace("\"", "&quot;")
         .replace("'", "&#x27;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> MarkdownParser {
+        MarkdownParser::new(vec![EmbedProvider::YouTube])
+    }
+
+    fn embed(provider: EmbedProvider, url: &str) -> BlogComponent {
+        BlogComponent::Embed {
+            provider,
+            url: url.to_string(),
+            title: Some("Demo".to_string()),
+        }
+    }
+
+    #[test]
+    fn unknown_components_only_show_in_previews() {
+        let component = BlogComponent::from_value_lenient(serde_json::json!({
+            "type": "hologram",
+            "note": "<script>alert(1)</script>",
+        }));
+        assert!(matches!(component, BlogComponent::Unknown(_)));
+
+        assert_eq!(render_component(&parser(), &component, false), "");
+
+        let preview = render_component(&parser(), &component, true);
+        assert!(preview.contains("hidden from readers"), "{}", preview);
+        assert!(preview.contains("&quot;hologram&quot;"), "{}", preview);
+        assert!(!preview.contains("<script>"), "{}", preview);
+    }
+
+    #[test]
+    fn embeds_load_nothing_before_a_click() {
+        let component = embed(
+            EmbedProvider::YouTube,
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+        );
+        let html = render_component(&parser(), &component, false);
+        assert!(
+            html.contains(r#"data-embed-src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ""#),
+            "{}",
+            html
+        );
+        assert!(html.contains(r#"<button type="button""#), "{}", html);
+        assert!(!html.contains("<iframe"), "{}", html);
+    }
+
+    #[test]
+    fn providers_off_the_allowlist_are_links() {
+        let html = render_component(
+            &parser(),
+            &embed(EmbedProvider::Vimeo, "https://vimeo.com/123"),
+            false,
+        );
+        assert!(!html.contains("data-embed-src"), "{}", html);
+        assert!(html.contains(r#"href="https://vimeo.com/123""#), "{}", html);
+    }
+
+    #[test]
+    fn embed_sources_must_be_on_an_allowed_host() {
+        let parser = parser();
+        for source in [
+            "https://evil.example/embed/x",
+            "http://www.youtube-nocookie.com/embed/x",
+            "https://player.vimeo.com/video/123",
+        ] {
+            let html = parser.sanitize_embed(&format!(
+                r#"<div data-embed-src="{}"><button type="button">Play</button></div>"#,
+                source
+            ));
+            assert!(!html.contains("data-embed-src"), "{}", html);
+        }
+    }
+
+    #[test]
+    fn markdown_cannot_make_embed_facades() {
+        let html = parser().parse(
+            r#"<div data-embed-src="https://www.youtube-nocookie.com/embed/x"><button type="button">Play</button></div>"#,
+        );
+        assert!(!html.contains("data-embed-src"), "{}", html);
+        assert!(!html.contains("<button"), "{}", html);
+    }
+}
@@ -0,0 +1,321 @@
+//! Server-side rendering of `math` and `diagram` components.
+//!
+//! Both are rendered to static markup (MathML and SVG) so readers don't need
+//! any client-side JavaScript. Rendering is comparatively expensive, so the
+//! output is cached by source text.
+//!
+//! The output goes into pages as is, so nothing an author writes may come
+//! out as markup: latex2mathml copies text, operators and identifiers into
+//! its MathML verbatim, and [`escape_mathml`] escapes them.
+
+use latex2mathml::{latex_to_mathml, DisplayStyle};
+use layout::backends::svg::SVGWriter;
+use layout::gv::{DotParser, GraphBuilder};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, OnceLock};
+
+use super::html_escape;
+
+/// Upper bound on cached renders; the cache is simply reset when full.
+const CACHE_CAPACITY: usize = 512;
+
+/// Renders by kind (`math` or `diagram`) and source.
+static CACHE: OnceLock<Mutex<HashMap<(&'static str, String), String>>> = OnceLock::new();
+
+/// Elements latex2mathml writes. `mro` is its misspelled end tag of sized
+/// parentheses.
+const MATHML_ELEMENTS: &[&str] = &[
+    "math",
+    "mfrac",
+    "mi",
+    "mn",
+    "mo",
+    "mover",
+    "mro",
+    "mroot",
+    "mrow",
+    "mspace",
+    "msqrt",
+    "mstyle",
+    "msub",
+    "msubsup",
+    "msup",
+    "mtable",
+    "mtd",
+    "mtext",
+    "mtr",
+    "munder",
+    "munderover",
+];
+
+/// Attributes latex2mathml writes on them.
+const MATHML_ATTRIBUTES: &[&str] = &[
+    "accent",
+    "columnalign",
+    "display",
+    "displaystyle",
+    "form",
+    "linethickness",
+    "mathvariant",
+    "maxsize",
+    "minsize",
+    "stretchy",
+    "width",
+    "xmlns",
+];
+
+pub fn render_math(latex: &str) -> String {
+    cached("math", latex, || {
+        match latex_to_mathml(latex, DisplayStyle::Block) {
+            Ok(mathml) => format!(
+                r#"<div class="overflow-x-auto text-lg text-center my-6">{}</div>"#,
+                escape_mathml(&mathml)
+            ),
+            Err(error) => render_error("equation", latex, &error.to_string()),
+        }
+    })
+}
+
+/// Render a diagram written in the Graphviz DOT language.
+pub fn render_diagram(source: &str, caption: Option<&str>) -> String {
+    let caption_html = caption
+        .map(|c| {
+            format!(
+                r#"<figcaption class="text-center text-gray-600 text-sm mt-2">{}</figcaption>"#,
+                html_escape(c)
+            )
+        })
+        .unwrap_or_default();
+
+    let diagram = cached("diagram", source, || match dot_to_svg(source) {
+        Ok(svg) => format!(
+            r#"<div class="overflow-x-auto flex justify-center">{}</div>"#,
+            svg
+        ),
+        Err(error) => render_error("diagram", source, &error),
+    });
+
+    format!(
+        r#"<figure class="mb-6">{}{}</figure>"#,
+        diagram, caption_html
+    )
+}
+
+/// Escape everything in `mathml` but the tags latex2mathml writes, so that
+/// `\text{<b>}` shows `<b>` and `a < b` stays well-formed.
+fn escape_mathml(mathml: &str) -> String {
+    let mut escaped = String::with_capacity(mathml.len());
+    let mut rest = mathml;
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '<' => match mathml_tag_len(rest) {
+                Some(len) => {
+                    escaped.push_str(&rest[..len]);
+                    len
+                }
+                None => {
+                    escaped.push_str("&lt;");
+                    1
+                }
+            },
+            '>' => {
+                escaped.push_str("&gt;");
+                1
+            }
+            '&' => match char_reference_len(rest) {
+                Some(len) => {
+                    escaped.push_str(&rest[..len]);
+                    len
+                }
+                None => {
+                    escaped.push_str("&amp;");
+                    1
+                }
+            },
+            c => {
+                escaped.push(c);
+                c.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+    escaped
+}
+
+/// Length of the tag `text` starts with, if latex2mathml could have written
+/// it: a known element with known attributes and plain values.
+fn mathml_tag_len(text: &str) -> Option<usize> {
+    let end = text.find('>')?;
+    let tag = &text[1..end];
+    let tag = tag.strip_prefix('/').unwrap_or(tag);
+    let tag = tag.strip_suffix('/').unwrap_or(tag);
+
+    let mut parts = tag.split(' ');
+    let name = parts.next()?;
+    let known = MATHML_ELEMENTS.contains(&name)
+        && parts.all(|attribute| {
+            let Some((name, value)) = attribute.split_once('=') else {
+                return false;
+            };
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            MATHML_ATTRIBUTES.contains(&name)
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '/'))
+        });
+    known.then_some(end + 1)
+}
+
+/// Length of the hexadecimal character reference, like `&#x2061;`, that
+/// `text` starts with.
+fn char_reference_len(text: &str) -> Option<usize> {
+    let digits = text.strip_prefix("&#x")?;
+    let end = digits.find(';')?;
+    (end > 0 && digits[..end].chars().all(|c| c.is_ascii_hexdigit())).then_some(end + 4)
+}
+
+fn dot_to_svg(source: &str) -> Result<String, String> {
+    let graph = DotParser::new(source).process()?;
+
+    // The layout engine asserts on some degenerate graphs instead of
+    // returning an error; treat those the same as a parse failure.
+    catch_unwind(AssertUnwindSafe(|| {
+        let mut builder = GraphBuilder::new();
+        builder.visit_graph(&graph);
+        let mut visual_graph = builder.get();
+        let mut svg = SVGWriter::new();
+        visual_graph.do_it(false, false, false, &mut svg);
+        svg.finalize()
+    }))
+    .map(|svg| inline_svg(&svg, source))
+    .map_err(|_| "the diagram could not be laid out".to_string())
+}
+
+/// Make the SVG document embeddable: drop the XML prolog and give element
+/// ids a per-diagram prefix so several diagrams can share one page.
+fn inline_svg(svg: &str, source: &str) -> String {
+    let prefix = format!("d{:x}-", source_hash("diagram", source));
+    let svg = svg.find("<svg").map_or(svg, |start| &svg[start..]);
+
+    svg.replace("id=\"", &format!("id=\"{}", prefix))
+        .replace("href=\"#", &format!("href=\"#{}", prefix))
+        .replace("url(#", &format!("url(#{}", prefix))
+}
+
+fn render_error(kind: &str, source: &str, error: &str) -> String {
+    format!(
+        r#"<div class="border-l-4 border-red-200 bg-red-50 text-red-800 p-4 mb-6 rounded-r-lg">
+                <p class="text-sm font-medium mb-2">Could not render this {}: {}</p>
+                <pre class="bg-gray-900 text-gray-100 p-4 rounded-lg overflow-x-auto"><code class="font-mono text-sm">{}</code></pre>
+               </div>"#,
+        kind,
+        html_escape(error),
+        html_escape(source)
+    )
+}
+
+/// A short tag for the ids of a diagram's SVG, keeping two diagrams on one
+/// page from sharing them.
+fn source_hash(kind: &str, source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.hash(&mut hasher);
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cached(kind: &'static str, source: &str, render: impl FnOnce() -> String) -> String {
+    let key = (kind, source.to_string());
+
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(html) = cache.lock().unwrap().get(&key) {
+        return html.clone();
+    }
+
+    let html = render();
+
+    let mut cache = cache.lock().unwrap();
+    if cache.len() >= CACHE_CAPACITY {
+        cache.clear();
+    }
+    cache.insert(key, html.clone());
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_text_in_equations() {
+        let html = render_math(r"\text{<img src=x onerror=alert(1)>}");
+        assert!(!html.contains("<img"), "{}", html);
+        assert!(html.contains("<mo>&gt;</mo>"), "{}", html);
+
+        assert_eq!(
+            escape_mathml(
+                r#"<mtext><img src=x onerror="alert(1)"></mtext><mo>&#x2061;</mo><mi mathvariant="normal">&</mi>"#
+            ),
+            r#"<mtext>&lt;img src=x onerror="alert(1)"&gt;</mtext><mo>&#x2061;</mo><mi mathvariant="normal">&amp;</mi>"#
+        );
+    }
+
+    #[test]
+    fn escapes_comparison_operators() {
+        let html = render_math("a < b > c");
+        assert!(html.contains("<mo>&lt;</mo>"), "{}", html);
+        assert!(html.contains("<mo>&gt;</mo>"), "{}", html);
+        assert!(
+            html.contains(r#"<math xmlns="http://www.w3.org/1998/Math/MathML" display="block">"#)
+        );
+    }
+
+    #[test]
+    fn keeps_markup_latex2mathml_writes() {
+        let html = render_math(r"\sin x + \begin{matrix} a & b \end{matrix}");
+        assert!(html.contains("<mi>sin</mi>"), "{}", html);
+        assert!(html.contains("<mtd>"), "{}", html);
+        assert!(!html.contains("&amp;"), "{}", html);
+    }
+
+    #[test]
+    fn shows_equations_that_fail_to_parse() {
+        let html = render_math(r"\frac{<script>");
+        assert!(html.contains("Could not render this equation"), "{}", html);
+        assert!(html.contains(r"\frac{&lt;script&gt;"), "{}", html);
+    }
+
+    #[test]
+    fn renders_diagrams_with_prefixed_ids() {
+        let html = render_diagram("digraph { a -> b }", Some("<Flow>"));
+        assert!(html.contains("<svg"), "{}", html);
+        assert!(!html.contains("<?xml"), "{}", html);
+        assert!(html.contains("&lt;Flow&gt;</figcaption>"), "{}", html);
+
+        let prefix = format!("id=\"d{:x}-", source_hash("diagram", "digraph { a -> b }"));
+        assert!(
+            !html.contains("id=\"") || html.contains(&prefix),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn shows_diagrams_that_fail_to_parse() {
+        let html = render_diagram("digraph { <b> ", None);
+        assert!(html.contains("Could not render this diagram"), "{}", html);
+        assert!(html.contains("digraph { &lt;b&gt;"), "{}", html);
+    }
+
+    #[test]
+    fn caches_by_kind_and_source() {
+        let math = render_math("x^2");
+        assert_eq!(render_math("x^2"), math);
+        assert_ne!(cached("diagram", "x^2", || "diagram".to_string()), math);
+    }
+}